@@ -1,6 +1,7 @@
 //! Examples of building DSN strings for various databases
 
 use dsn::DSNBuilder;
+use std::time::Duration;
 
 fn main() {
     println!("=== DSN Builder Examples ===\n");
@@ -12,8 +13,8 @@ fn main() {
         .password("my-secret-pw")
         .host("db.example.com")
         .database("myapp")
-        .param("charset", "utf8mb4")
-        .param("parseTime", "true")
+        .charset("utf8mb4")
+        .parse_time(true)
         .build();
     println!("   {mysql}\n");
 
@@ -87,7 +88,7 @@ fn main() {
         .password("secure-password")
         .host("mariadb.local")
         .database("app_data")
-        .param("timeout", "30s")
+        .connect_timeout(Duration::from_secs(30))
         .build();
     println!("   {mariadb}\n");
 
@@ -1,6 +1,7 @@
 //! Example showing `PostgreSQL` DSN with different SSL modes
 
-use dsn::{DSNBuilder, parse};
+use dsn::{DSNBuilder, parse, postgres::SslMode};
+use std::time::Duration;
 
 fn main() {
     println!("=== PostgreSQL SSL Mode Examples ===\n");
@@ -13,7 +14,7 @@ fn main() {
         .password("secret")
         .host("prod.postgres.com")
         .database("app_db")
-        .param("sslmode", "require")
+        .sslmode(SslMode::Require)
         .build();
     println!("   Production (SSL required):");
     println!("   {ssl_require}\n");
@@ -23,7 +24,7 @@ fn main() {
         .password("secret")
         .host("staging.postgres.com")
         .database("app_db")
-        .param("sslmode", "prefer")
+        .sslmode(SslMode::Prefer)
         .build();
     println!("   Staging (SSL preferred):");
     println!("   {ssl_prefer}\n");
@@ -33,7 +34,7 @@ fn main() {
         .password("dev123")
         .host("localhost")
         .database("dev_db")
-        .param("sslmode", "disable")
+        .sslmode(SslMode::Disable)
         .build();
     println!("   Development (SSL disabled):");
     println!("   {ssl_disable}\n");
@@ -54,28 +55,25 @@ fn main() {
                 println!("   Host: {}", dsn.host.as_ref().unwrap());
                 println!("   Database: {}", dsn.database.as_ref().unwrap());
 
-                if let Some(sslmode) = dsn.params.get("sslmode") {
-                    println!("   SSL Mode: {sslmode}");
-
-                    match sslmode.as_str() {
-                        "disable" => {
-                            println!(
-                                "   [WARNING] SSL is disabled - not recommended for production!"
-                            );
-                        }
-                        "require" => println!("   [OK] SSL is required - secure connection"),
-                        "prefer" => println!("   [INFO] SSL is preferred - will use if available"),
-                        "verify-ca" => println!("   [SECURE] SSL with CA verification"),
-                        "verify-full" => println!("   [SECURE] SSL with full verification"),
-                        _ => println!("   [UNKNOWN] Unknown SSL mode: {sslmode}"),
+                let pg = dsn.postgres();
+                match pg.sslmode() {
+                    Ok(Some(sslmode)) => {
+                        println!("   SSL Mode: {sslmode}");
+                        println!("   {}", describe(sslmode));
                     }
-                } else {
-                    println!("   [INFO] No SSL mode specified (will use PostgreSQL default)");
+                    Ok(None) => {
+                        println!("   [INFO] No SSL mode specified (will use PostgreSQL default)");
+                    }
+                    Err(e) => println!("   [UNKNOWN] {e}"),
                 }
 
                 // Check for other connection parameters
-                if let Some(timeout) = dsn.params.get("connect_timeout") {
-                    println!("   Connection timeout: {timeout}s");
+                if let Ok(Some(timeout)) = pg.connect_timeout() {
+                    println!("   Connection timeout: {}s", timeout.as_secs());
+                }
+
+                if let Err(e) = pg.validate() {
+                    println!("   [WARNING] {e}");
                 }
 
                 println!();
@@ -97,8 +95,8 @@ fn main() {
             .password("prod_pass")
             .host("prod.postgres.com")
             .database("prod_db")
-            .param("sslmode", "require")
-            .param("connect_timeout", "30")
+            .sslmode(SslMode::Require)
+            .connect_timeout(Duration::from_secs(30))
             .build(),
 
         "staging" => DSNBuilder::postgres()
@@ -106,8 +104,8 @@ fn main() {
             .password("stage_pass")
             .host("staging.postgres.com")
             .database("stage_db")
-            .param("sslmode", "prefer")
-            .param("connect_timeout", "10")
+            .sslmode(SslMode::Prefer)
+            .connect_timeout(Duration::from_secs(10))
             .build(),
 
         _ => DSNBuilder::postgres()
@@ -115,10 +113,22 @@ fn main() {
             .password("dev")
             .host("localhost")
             .database("dev_db")
-            .param("sslmode", "disable")
+            .sslmode(SslMode::Disable)
             .build(),
     };
 
     println!("   Environment: {environment}");
     println!("   Connection string: {dsn}");
 }
+
+/// Describe what a libpq `sslmode` means for the connection
+const fn describe(sslmode: SslMode) -> &'static str {
+    match sslmode {
+        SslMode::Disable => "[WARNING] SSL is disabled - not recommended for production!",
+        SslMode::Allow => "[INFO] SSL is allowed - used only if the server requires it",
+        SslMode::Prefer => "[INFO] SSL is preferred - will use if available",
+        SslMode::Require => "[OK] SSL is required - secure connection",
+        SslMode::VerifyCa => "[SECURE] SSL with CA verification",
+        SslMode::VerifyFull => "[SECURE] SSL with full verification",
+    }
+}
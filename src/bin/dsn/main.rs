@@ -0,0 +1,280 @@
+//! `dsn` command-line tool to inspect, validate and convert DSNs
+//!
+//! Commands taking a DSN read it from standard input when it is left out, so
+//! passwords do not have to appear in the process list.
+
+mod convert;
+mod output;
+
+use clap::{Parser, Subcommand};
+use dsn::{
+    DSN, DSNBuilder, parse,
+    probe::{Handshake, Probe},
+};
+use std::{
+    io::{self, BufRead},
+    process::ExitCode,
+    time::Duration,
+};
+
+/// Inspect, validate and convert DSNs
+#[derive(Parser)]
+#[command(name = "dsn", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the fields of a DSN
+    Parse {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = output::Format::Json)]
+        format: output::Format,
+        /// Print the password instead of redacting it
+        #[arg(long)]
+        show_password: bool,
+        /// DSN, read from standard input when omitted
+        dsn: Option<String>,
+    },
+    /// Exit with status 1 unless every DSN is valid
+    Validate {
+        /// Also check the parameters known to the driver
+        #[arg(long)]
+        params: bool,
+        /// DSNs, one per line from standard input when omitted
+        dsns: Vec<String>,
+    },
+    /// Print the DSN with its password redacted
+    Redact {
+        /// DSN, read from standard input when omitted
+        dsn: Option<String>,
+    },
+    /// Convert into another connection string format
+    Convert {
+        /// Target format
+        #[arg(long, value_enum)]
+        to: convert::Format,
+        /// DSN, read from standard input when omitted
+        dsn: Option<String>,
+    },
+    /// Print a single field, `param.<name>` for a parameter
+    Get {
+        /// driver, username, password, protocol, address, host, port,
+        /// database, socket or param.<name>
+        field: String,
+        /// DSN, read from standard input when omitted
+        dsn: Option<String>,
+    },
+    /// Exit with status 1 unless a target of the DSN is reachable
+    Probe {
+        /// Seconds to wait for each TCP connect, at least 1
+        #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: u64,
+        /// Speak the database protocol to confirm the server type and version
+        #[arg(long)]
+        handshake: bool,
+        /// DSN, read from standard input when omitted
+        dsn: Option<String>,
+    },
+    /// Print the JSON Schema of the `parse --format json` output
+    Schema,
+    /// Build a DSN from its components, applying the driver's default port
+    Build {
+        #[arg(long)]
+        driver: String,
+        #[arg(long)]
+        username: Option<String>,
+        #[arg(long)]
+        password: Option<String>,
+        #[arg(long)]
+        host: Option<String>,
+        #[arg(long)]
+        port: Option<u16>,
+        #[arg(long, conflicts_with = "host")]
+        socket: Option<String>,
+        #[arg(long)]
+        database: Option<String>,
+        /// Query parameter as `key=value`, may be repeated
+        #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_param)]
+        params: Vec<(String, String)>,
+    },
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("dsn: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> Result<ExitCode, String> {
+    match command {
+        Command::Parse {
+            format,
+            show_password,
+            dsn,
+        } => {
+            let dsn = read_dsn(dsn)?;
+            print!("{}", output::render(&dsn, format, show_password));
+        }
+        Command::Probe {
+            timeout,
+            handshake,
+            dsn,
+        } => {
+            let dsn = read_dsn(dsn)?;
+            let timeout = Duration::from_secs(timeout);
+            let ok = if handshake {
+                let handshakes = dsn
+                    .handshake(timeout)
+                    .map_err(|err| format!("probe: {err}"))?;
+                for handshake in &handshakes {
+                    println!("{handshake}");
+                }
+                handshakes.iter().any(Handshake::is_confirmed)
+            } else {
+                let probes = dsn.probe(timeout).map_err(|err| format!("probe: {err}"))?;
+                for probe in &probes {
+                    println!("{probe}");
+                }
+                probes.iter().any(Probe::is_reachable)
+            };
+            if !ok {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Schema => print!("{}", dsn::json::SCHEMA),
+        Command::Validate { params, dsns } => return Ok(validate(dsns, params)),
+        Command::Redact { dsn } => println!("{}", output::redact(&read_dsn(dsn)?)),
+        Command::Convert { to, dsn } => println!("{}", convert::convert(&read_dsn(dsn)?, to)?),
+        Command::Get { field, dsn } => match output::get(&read_dsn(dsn)?, &field)? {
+            Some(value) => println!("{value}"),
+            None => return Ok(ExitCode::FAILURE),
+        },
+        Command::Build {
+            driver,
+            username,
+            password,
+            host,
+            port,
+            socket,
+            database,
+            params,
+        } => {
+            let mut builder = preset(&driver);
+            if let Some(username) = username {
+                builder = builder.username(username);
+            }
+            if let Some(password) = password {
+                builder = builder.password(password);
+            }
+            if let Some(host) = host {
+                builder = builder.host(host);
+            }
+            if let Some(port) = port {
+                builder = builder.port(port);
+            }
+            if let Some(socket) = socket {
+                builder = builder.socket(socket);
+            }
+            if let Some(database) = database {
+                builder = builder.database(database);
+            }
+            for (key, value) in params {
+                builder = builder.param(key, value);
+            }
+            println!("{}", builder.build());
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Parse the DSN argument, or the first line of standard input
+fn read_dsn(dsn: Option<String>) -> Result<DSN, String> {
+    let input = match dsn {
+        Some(dsn) if dsn != "-" => dsn,
+        _ => io::stdin()
+            .lock()
+            .lines()
+            .next()
+            .transpose()
+            .map_err(|err| format!("reading standard input: {err}"))?
+            .ok_or("no DSN given")?,
+    };
+
+    parse(input.trim_end()).map_err(|err| format!("invalid DSN: {err}"))
+}
+
+/// Report every invalid DSN on standard error
+fn validate(dsns: Vec<String>, params: bool) -> ExitCode {
+    let dsns = if dsns.is_empty() {
+        io::stdin()
+            .lock()
+            .lines()
+            .map_while(Result::ok)
+            .filter(|line| !line.trim().is_empty())
+            .collect()
+    } else {
+        dsns
+    };
+
+    let mut valid = true;
+    for (i, input) in dsns.iter().enumerate() {
+        let result = parse(input.trim_end())
+            .map_err(|err| err.to_string())
+            .and_then(|dsn| {
+                if params {
+                    dsn.validate_params().map_err(|err| err.to_string())
+                } else {
+                    Ok(())
+                }
+            });
+        if let Err(err) = result {
+            // the input may hold a password, refer to it by position
+            eprintln!("dsn {}: {err}", i + 1);
+            valid = false;
+        }
+    }
+
+    if valid {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Builder preset for `driver`, falling back to a plain builder
+fn preset(driver: &str) -> DSNBuilder {
+    match driver {
+        "mysql" => DSNBuilder::mysql(),
+        "mariadb" => DSNBuilder::mariadb(),
+        "postgres" => DSNBuilder::postgres(),
+        "redis" => DSNBuilder::redis(),
+        "oracle" => DSNBuilder::oracle(),
+        "clickhouse" => DSNBuilder::clickhouse(),
+        "cassandra" => DSNBuilder::cassandra(),
+        "scylladb" => DSNBuilder::scylladb(),
+        "elasticsearch" => DSNBuilder::elasticsearch(),
+        "opensearch" => DSNBuilder::opensearch(),
+        "amqp" => DSNBuilder::amqp(),
+        "amqps" => DSNBuilder::amqps(),
+        "kafka" => DSNBuilder::kafka(),
+        "nats" => DSNBuilder::nats(),
+        "mqtt" => DSNBuilder::mqtt(),
+        "mqtts" => DSNBuilder::mqtts(),
+        _ => DSNBuilder::default().driver(driver),
+    }
+}
+
+fn parse_param(param: &str) -> Result<(String, String), String> {
+    param
+        .split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, got `{param}`"))
+}
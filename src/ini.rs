@@ -0,0 +1,112 @@
+//! Minimal INI reader shared by the `my.cnf` and `pg_service.conf` loaders
+//!
+//! Only the line structure is handled here, value quoting and escaping differ
+//! between the formats and are left to the callers.
+
+/// A significant line of an INI file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line<'a> {
+    /// `[name]`
+    Section(&'a str),
+    /// `key = value`, or a bare `key` without value
+    Entry {
+        key: &'a str,
+        value: Option<&'a str>,
+    },
+    /// `!name argument`, e.g. `!include /etc/mysql/conf.d/client.cnf`
+    Directive { name: &'a str, argument: &'a str },
+}
+
+/// Parse `contents` into its significant lines, skipping blank lines and
+/// `#`/`;` comments
+///
+/// Returns the 1-based number of the first malformed line on error.
+pub fn parse(contents: &str) -> Result<Vec<Line<'_>>, usize> {
+    let mut lines = Vec::new();
+
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        let parsed = if let Some(section) = line.strip_prefix('[') {
+            let name = section.strip_suffix(']').ok_or(number + 1)?.trim();
+            if name.is_empty() {
+                return Err(number + 1);
+            }
+            Line::Section(name)
+        } else if let Some(directive) = line.strip_prefix('!') {
+            let (name, argument) = directive
+                .split_once(char::is_whitespace)
+                .ok_or(number + 1)?;
+            Line::Directive {
+                name,
+                argument: argument.trim(),
+            }
+        } else {
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim_end(), Some(value.trim_start())),
+                None => (line, None),
+            };
+            if key.is_empty() {
+                return Err(number + 1);
+            }
+            Line::Entry { key, value }
+        };
+        lines.push(parsed);
+    }
+
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Line, parse};
+
+    #[test]
+    fn test_parse() {
+        let contents = "\
+# comment
+; comment
+
+[client]
+host = db.example.com
+port=3306
+skip-ssl
+!include /etc/mysql/extra.cnf
+[ service ]
+";
+        assert_eq!(
+            parse(contents).unwrap(),
+            [
+                Line::Section("client"),
+                Line::Entry {
+                    key: "host",
+                    value: Some("db.example.com")
+                },
+                Line::Entry {
+                    key: "port",
+                    value: Some("3306")
+                },
+                Line::Entry {
+                    key: "skip-ssl",
+                    value: None
+                },
+                Line::Directive {
+                    name: "include",
+                    argument: "/etc/mysql/extra.cnf"
+                },
+                Line::Section("service"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse("[client\nhost=x"), Err(1));
+        assert_eq!(parse("[client]\n[]"), Err(2));
+        assert_eq!(parse("[client]\n= value"), Err(2));
+        assert_eq!(parse("!include"), Err(1));
+    }
+}
@@ -0,0 +1,242 @@
+//! Connection targets of a `DSN` as socket addresses
+//!
+//! [`DSN::endpoints`] resolves every host of the address, applying the
+//! driver's [`default_port`] when the DSN has none, and yields a
+//! [`Endpoint`] per socket address or the path of a Unix domain socket.
+//! `DSN` implements [`ToSocketAddrs`], so networking code can take a DSN
+//! directly:
+//!
+//! ```no_run
+//! use std::net::TcpStream;
+//!
+//! let dsn = dsn::parse("redis://tcp(127.0.0.1)/0").unwrap();
+//! let stream = TcpStream::connect(&dsn)?; // 127.0.0.1:6379
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::{DSN, params};
+use std::{
+    fmt, io,
+    net::{SocketAddr, ToSocketAddrs},
+    path::PathBuf,
+    vec,
+};
+
+/// A resolved connection target
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// TCP (or UDP) socket address
+    Tcp(SocketAddr),
+    /// Path of a Unix domain socket
+    Unix(PathBuf),
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{addr}"),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// Default port of `driver`, the same the `DSNBuilder` presets use
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(dsn::net::default_port("postgres"), Some(5432));
+/// assert_eq!(dsn::net::default_port("sqlite"), None);
+/// ```
+#[must_use]
+pub fn default_port(driver: &str) -> Option<u16> {
+    let port = match driver {
+        driver if params::is_postgres(driver) => crate::postgres::DEFAULT_PORT,
+        driver if params::is_mysql(driver) => 3306,
+        driver if params::is_clickhouse(driver) => 9000,
+        driver if params::is_cassandra(driver) => 9042,
+        driver if params::is_elasticsearch(driver) => 9200,
+        "redis" | "rediss" => 6379,
+        "oracle" => crate::oracle::DEFAULT_PORT,
+        "amqp" => 5672,
+        "amqps" => 5671,
+        "kafka" => 9092,
+        "nats" => 4222,
+        "mqtt" => 1883,
+        "mqtts" => 8883,
+        "mongodb" => 27017,
+        "sqlserver" | "mssql" => 1433,
+        _ => return None,
+    };
+    Some(port)
+}
+
+impl DSN {
+    /// Resolve the connection targets, in the order of the address
+    ///
+    /// Every host of a `tcp` (or other network) address is resolved with
+    /// [`ToSocketAddrs`], falling back to the driver's [`default_port`]; a
+    /// `unix` address yields its socket path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidInput` if the DSN has no address, is a
+    /// `file` DSN, or a host has no port and the driver no default port, and
+    /// the resolver's error if a host does not resolve.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dsn::{net::Endpoint, parse};
+    ///
+    /// let dsn = parse("postgres://app@tcp(127.0.0.1,[::1]:5433)/app").unwrap();
+    /// let endpoints: Vec<_> = dsn.endpoints().unwrap().collect();
+    ///
+    /// assert_eq!(
+    ///     endpoints,
+    ///     [
+    ///         Endpoint::Tcp("127.0.0.1:5432".parse().unwrap()),
+    ///         Endpoint::Tcp("[::1]:5433".parse().unwrap()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn endpoints(&self) -> io::Result<vec::IntoIter<Endpoint>> {
+        match self.protocol.as_str() {
+            "" => Err(invalid_input("DSN has no address")),
+            "file" => Err(invalid_input("file DSN has no network endpoint")),
+            "unix" => Ok(self
+                .socket
+                .iter()
+                .map(|socket| Endpoint::Unix(PathBuf::from(socket)))
+                .collect::<Vec<_>>()
+                .into_iter()),
+            _ => {
+                let mut endpoints = Vec::new();
+                for (host, port) in self.hosts() {
+                    let port = port.or_else(|| default_port(&self.driver)).ok_or_else(|| {
+                        invalid_input(&format!(
+                            "no port for host {host} and driver {}",
+                            self.driver
+                        ))
+                    })?;
+                    endpoints.extend((host, port).to_socket_addrs()?.map(Endpoint::Tcp));
+                }
+                Ok(endpoints.into_iter())
+            }
+        }
+    }
+}
+
+/// Socket addresses of every host, see [`DSN::endpoints`]; a Unix socket DSN
+/// has none and fails with `InvalidInput`
+impl ToSocketAddrs for DSN {
+    type Iter = vec::IntoIter<SocketAddr>;
+
+    fn to_socket_addrs(&self) -> io::Result<Self::Iter> {
+        self.endpoints()?
+            .map(|endpoint| match endpoint {
+                Endpoint::Tcp(addr) => Ok(addr),
+                Endpoint::Unix(_) => Err(invalid_input("unix socket DSN has no socket address")),
+            })
+            .collect::<io::Result<Vec<_>>>()
+            .map(Vec::into_iter)
+    }
+}
+
+fn invalid_input(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Endpoint, default_port};
+    use crate::{DSNBuilder, parse};
+    use std::{
+        io,
+        net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+        path::PathBuf,
+    };
+
+    fn addr(addr: &str) -> SocketAddr {
+        addr.parse().unwrap()
+    }
+
+    #[test]
+    fn test_endpoints() {
+        let dsn = parse("kafka://tcp(127.0.0.1:9092,10.0.0.2,[::1]:9093)").unwrap();
+        assert_eq!(
+            dsn.endpoints().unwrap().collect::<Vec<_>>(),
+            [
+                Endpoint::Tcp(addr("127.0.0.1:9092")),
+                Endpoint::Tcp(addr("10.0.0.2:9092")),
+                Endpoint::Tcp(addr("[::1]:9093")),
+            ]
+        );
+
+        let dsn = parse("mysql://root@unix(/run/mysqld/mysqld.sock)/app").unwrap();
+        let endpoints = dsn.endpoints().unwrap().collect::<Vec<_>>();
+        assert_eq!(
+            endpoints,
+            [Endpoint::Unix(PathBuf::from("/run/mysqld/mysqld.sock"))]
+        );
+        assert_eq!(endpoints[0].to_string(), "unix:/run/mysqld/mysqld.sock");
+
+        let dsn = parse("redis://tcp(localhost)/0").unwrap();
+        let addrs = dsn.to_socket_addrs().unwrap().collect::<Vec<_>>();
+        assert!(!addrs.is_empty());
+        assert!(
+            addrs
+                .iter()
+                .all(|addr| addr.ip().is_loopback() && addr.port() == 6379)
+        );
+    }
+
+    #[test]
+    fn test_endpoint_errors() {
+        for input in [
+            "postgres://app@/app?service=reporting",
+            "sqlite://file(/var/lib/app.db)",
+            "custom://tcp(127.0.0.1)/app",
+        ] {
+            let err = parse(input).unwrap().endpoints().unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{input}");
+        }
+
+        let dsn = parse("mysql://root@unix(/run/mysqld/mysqld.sock)/app").unwrap();
+        assert_eq!(
+            dsn.to_socket_addrs().unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_connect() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let dsn = DSNBuilder::postgres().host("127.0.0.1").port(port).build();
+
+        let stream = TcpStream::connect(&dsn).unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), listener.local_addr().unwrap());
+    }
+
+    #[test]
+    fn test_default_port() {
+        for (driver, port) in [
+            (DSNBuilder::mysql(), 3306),
+            (DSNBuilder::postgres(), 5432),
+            (DSNBuilder::redis(), 6379),
+            (DSNBuilder::oracle(), 1521),
+            (DSNBuilder::clickhouse(), 9000),
+            (DSNBuilder::scylladb(), 9042),
+            (DSNBuilder::opensearch(), 9200),
+            (DSNBuilder::amqps(), 5671),
+            (DSNBuilder::nats(), 4222),
+            (DSNBuilder::mqtts(), 8883),
+        ] {
+            let dsn = driver.host("localhost").build();
+            assert_eq!(default_port(&dsn.driver), Some(port), "{}", dsn.driver);
+            assert_eq!(dsn.port, Some(port), "{}", dsn.driver);
+        }
+        assert_eq!(default_port("sqlite"), None);
+    }
+}
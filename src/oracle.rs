@@ -0,0 +1,700 @@
+//! Oracle connect strings
+//!
+//! Oracle clients identify a database either with an `EZConnect` string:
+//!
+//! ```text
+//! [user[/password]@][tcp://|tcps://]host[:port][/service_name][:server][/instance_name]
+//! ```
+//!
+//! the legacy JDBC thin form `host:port:SID`, or a full TNS connect descriptor:
+//!
+//! ```text
+//! (DESCRIPTION=(ADDRESS=(PROTOCOL=TCP)(HOST=h)(PORT=1521))(CONNECT_DATA=(SERVICE_NAME=s)))
+//! ```
+//!
+//! Both are parsed into a regular `DSN` with driver `oracle`. A service name is
+//! stored in `DSN::database`, while a SID is stored in the `sid` parameter so
+//! the two can be told apart, see [`connect_data`].
+//!
+//! # Examples
+//!
+//! ```
+//! use dsn::oracle::{self, ConnectData};
+//!
+//! let dsn = oracle::parse("scott/tiger@db.example.com:1521/ORCLPDB1").unwrap();
+//! assert_eq!(dsn.driver, "oracle");
+//! assert_eq!(dsn.host.as_deref(), Some("db.example.com"));
+//! assert_eq!(
+//!     oracle::connect_data(&dsn),
+//!     Some(ConnectData::ServiceName("ORCLPDB1".to_string()))
+//! );
+//! ```
+
+use crate::{DSN, ParseError};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+
+/// Default Oracle listener port
+pub const DEFAULT_PORT: u16 = 1521;
+
+/// How the database is identified inside `CONNECT_DATA`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectData {
+    /// `SERVICE_NAME=...`, stored in `DSN::database`
+    ServiceName(String),
+    /// `SID=...`, stored in the `sid` parameter
+    Sid(String),
+}
+
+/// Parse an Oracle `EZConnect` string or TNS connect descriptor into a `DSN`
+///
+/// Input starting with `(` (optionally preceded by `user/password@`) is
+/// treated as a TNS descriptor, anything else as `EZConnect`.
+///
+/// # Errors
+///
+/// Returns `ParseError` if the connect string is malformed, see
+/// [`parse_ezconnect`] and [`parse_descriptor`].
+///
+/// # Examples
+///
+/// ```
+/// use dsn::oracle;
+///
+/// let dsn = oracle::parse(
+///     "(DESCRIPTION=(ADDRESS=(PROTOCOL=TCP)(HOST=h)(PORT=1522))(CONNECT_DATA=(SID=ORCL)))",
+/// )
+/// .unwrap();
+/// assert_eq!(dsn.port, Some(1522));
+/// assert_eq!(dsn.params.get("sid").unwrap(), "ORCL");
+/// ```
+pub fn parse(input: &str) -> Result<DSN, ParseError> {
+    let (_, target) = split_credentials(input.trim());
+    if target.trim_start().starts_with('(') {
+        parse_descriptor(input)
+    } else {
+        parse_ezconnect(input)
+    }
+}
+
+/// Parse an `EZConnect` string (or the legacy `host:port:SID` form)
+///
+/// IPv6 hosts are written in brackets, `[::1]:1521/service`.
+///
+/// # Errors
+///
+/// Returns `ParseError` in the following cases:
+/// - `InvalidProtocol` - Prefix other than `tcp://` or `tcps://`
+/// - `MissingHost` - Host is empty
+/// - `InvalidPort` - Port number is invalid or out of range
+///
+/// # Examples
+///
+/// ```
+/// use dsn::oracle::{self, ConnectData};
+///
+/// let dsn = oracle::parse_ezconnect("db:1521:ORCL").unwrap();
+/// assert_eq!(oracle::connect_data(&dsn), Some(ConnectData::Sid("ORCL".to_string())));
+///
+/// let dsn = oracle::parse_ezconnect("tcps://db/sales:pooled").unwrap();
+/// assert_eq!(dsn.protocol, "tcps");
+/// assert_eq!(dsn.port, Some(1521));
+/// assert_eq!(dsn.database.as_deref(), Some("sales"));
+/// assert_eq!(dsn.params.get("server").unwrap(), "pooled");
+/// ```
+pub fn parse_ezconnect(input: &str) -> Result<DSN, ParseError> {
+    let mut dsn = new_dsn();
+    let (credentials, mut rest) = split_credentials(input.trim());
+    set_credentials(&mut dsn, credentials);
+
+    if let Some((scheme, tail)) = rest.split_once("://") {
+        dsn.protocol = protocol(scheme)?;
+        rest = tail;
+    }
+    rest = rest.strip_prefix("//").unwrap_or(rest);
+
+    // host[:port] ends at the first '/'
+    let (address, path) = rest
+        .split_once('/')
+        .map_or((rest, None), |(a, p)| (a, Some(p)));
+
+    // [ipv6]:port:SID, the colons of the host are inside the brackets
+    let (host, rest) = if let Some(bracketed) = address.strip_prefix('[') {
+        let (host, rest) = bracketed.split_once(']').ok_or(ParseError::MissingHost)?;
+        if !rest.is_empty() && !rest.starts_with(':') {
+            return Err(ParseError::InvalidPort);
+        }
+        (host, rest.strip_prefix(':').unwrap_or(rest))
+    } else {
+        address.split_once(':').unwrap_or((address, ""))
+    };
+    let mut parts = rest.splitn(2, ':');
+    if host.is_empty() {
+        return Err(ParseError::MissingHost);
+    }
+    let port = match parts.next() {
+        Some(port) if !port.is_empty() => {
+            port.parse::<u16>().map_err(|_| ParseError::InvalidPort)?
+        }
+        _ => DEFAULT_PORT,
+    };
+    // host:port:SID
+    if let Some(sid) = parts.next().filter(|sid| !sid.is_empty()) {
+        dsn.params.insert("sid".to_string(), sid.to_string());
+    }
+    set_address(&mut dsn, host, port);
+
+    // [service_name][:server][/instance_name]
+    if let Some(path) = path {
+        let (service, instance) = path
+            .split_once('/')
+            .map_or((path, None), |(s, i)| (s, Some(i)));
+        let (service, server) = service
+            .split_once(':')
+            .map_or((service, None), |(s, t)| (s, Some(t)));
+        if !service.is_empty() {
+            dsn.database = Some(service.to_string());
+        }
+        if let Some(server) = server.filter(|s| !s.is_empty()) {
+            dsn.params
+                .insert("server".to_string(), server.to_ascii_lowercase());
+        }
+        if let Some(instance) = instance.filter(|i| !i.is_empty()) {
+            dsn.params
+                .insert("instance_name".to_string(), instance.to_string());
+        }
+    }
+
+    Ok(dsn)
+}
+
+/// Parse a TNS connect descriptor
+///
+/// Keywords are case-insensitive. When the descriptor lists several addresses
+/// (`ADDRESS_LIST` or `DESCRIPTION_LIST`) the first one is used.
+///
+/// # Errors
+///
+/// Returns `ParseError` in the following cases:
+/// - `InvalidDescriptor` - Unbalanced parentheses, missing `DESCRIPTION` or
+///   text after the descriptor
+/// - `MissingAddress` - No `ADDRESS` in the descriptor
+/// - `InvalidProtocol` - `PROTOCOL` other than `TCP` or `TCPS`
+/// - `MissingHost` - `HOST` is missing
+/// - `InvalidPort` - `PORT` is invalid or out of range
+///
+/// # Examples
+///
+/// ```
+/// use dsn::oracle;
+///
+/// let dsn = oracle::parse_descriptor(
+///     "(DESCRIPTION=(ADDRESS=(PROTOCOL=TCP)(HOST=h)(PORT=1521))(CONNECT_DATA=(SERVICE_NAME=s)))",
+/// )
+/// .unwrap();
+/// assert_eq!(dsn.host.as_deref(), Some("h"));
+/// assert_eq!(dsn.database.as_deref(), Some("s"));
+/// ```
+pub fn parse_descriptor(input: &str) -> Result<DSN, ParseError> {
+    let mut dsn = new_dsn();
+    let (credentials, rest) = split_credentials(input.trim());
+    set_credentials(&mut dsn, credentials);
+
+    let mut parser = Parser::new(rest);
+    let nodes = parser.parse_list()?;
+    if parser.peek().is_some() {
+        return Err(ParseError::InvalidDescriptor);
+    }
+    let description = first_description(&nodes).ok_or(ParseError::InvalidDescriptor)?;
+
+    let address = find(description, "ADDRESS")
+        .or_else(|| find(description, "ADDRESS_LIST").and_then(|list| find(list, "ADDRESS")))
+        .ok_or(ParseError::MissingAddress)?;
+
+    if let Some(proto) = text(address, "PROTOCOL") {
+        dsn.protocol = protocol(proto)?;
+    }
+    let host = text(address, "HOST")
+        .filter(|host| !host.is_empty())
+        .ok_or(ParseError::MissingHost)?;
+    let port = text(address, "PORT").map_or(Ok(DEFAULT_PORT), |port| {
+        port.parse::<u16>().map_err(|_| ParseError::InvalidPort)
+    })?;
+    set_address(&mut dsn, host, port);
+
+    if let Some(connect_data) = find(description, "CONNECT_DATA") {
+        if let Some(service) = text(connect_data, "SERVICE_NAME") {
+            dsn.database = Some(service.to_string());
+        } else if let Some(sid) = text(connect_data, "SID") {
+            dsn.params.insert("sid".to_string(), sid.to_string());
+        }
+        if let Some(server) = text(connect_data, "SERVER") {
+            dsn.params
+                .insert("server".to_string(), server.to_ascii_lowercase());
+        }
+        if let Some(instance) = text(connect_data, "INSTANCE_NAME") {
+            dsn.params
+                .insert("instance_name".to_string(), instance.to_string());
+        }
+    }
+
+    Ok(dsn)
+}
+
+/// Return how the database is identified, service name or SID
+///
+/// The `sid` parameter takes precedence over `DSN::database`.
+#[must_use]
+pub fn connect_data(dsn: &DSN) -> Option<ConnectData> {
+    dsn.params.get("sid").map_or_else(
+        || dsn.database.clone().map(ConnectData::ServiceName),
+        |sid| Some(ConnectData::Sid(sid.clone())),
+    )
+}
+
+/// Render a `DSN` as an `EZConnect` string (without credentials)
+///
+/// A SID renders in the `host:port:SID` form since `EZConnect` itself has no
+/// way to express it.
+///
+/// # Examples
+///
+/// ```
+/// use dsn::DSNBuilder;
+///
+/// let dsn = DSNBuilder::oracle()
+///     .host("db.example.com")
+///     .service_name("ORCLPDB1")
+///     .build();
+///
+/// assert_eq!(dsn::oracle::to_ezconnect(&dsn), "db.example.com:1521/ORCLPDB1");
+/// ```
+#[must_use]
+pub fn to_ezconnect(dsn: &DSN) -> String {
+    let mut out = String::new();
+    if dsn.protocol == "tcps" {
+        out.push_str("tcps://");
+    }
+    out.push_str(&crate::format_host_port(
+        dsn.host.as_deref().unwrap_or("localhost"),
+        Some(dsn.port.unwrap_or(DEFAULT_PORT)),
+    ));
+
+    match connect_data(dsn) {
+        Some(ConnectData::Sid(sid)) => {
+            let _ = write!(out, ":{sid}");
+        }
+        Some(ConnectData::ServiceName(service)) => {
+            let _ = write!(out, "/{service}");
+            if let Some(server) = dsn.params.get("server") {
+                let _ = write!(out, ":{server}");
+            }
+            if let Some(instance) = dsn.params.get("instance_name") {
+                let _ = write!(out, "/{instance}");
+            }
+        }
+        None => {}
+    }
+    out
+}
+
+/// Render a `DSN` as a TNS connect descriptor (without credentials)
+///
+/// # Examples
+///
+/// ```
+/// use dsn::DSNBuilder;
+///
+/// let dsn = DSNBuilder::oracle().host("h").sid("ORCL").build();
+///
+/// assert_eq!(
+///     dsn::oracle::to_descriptor(&dsn),
+///     "(DESCRIPTION=(ADDRESS=(PROTOCOL=TCP)(HOST=h)(PORT=1521))(CONNECT_DATA=(SID=ORCL)))"
+/// );
+/// ```
+#[must_use]
+pub fn to_descriptor(dsn: &DSN) -> String {
+    let mut out = format!(
+        "(DESCRIPTION=(ADDRESS=(PROTOCOL={})(HOST={})(PORT={}))",
+        dsn.protocol.to_ascii_uppercase(),
+        dsn.host.as_deref().unwrap_or("localhost"),
+        dsn.port.unwrap_or(DEFAULT_PORT)
+    );
+
+    let mut connect_data = String::new();
+    match self::connect_data(dsn) {
+        Some(ConnectData::Sid(sid)) => {
+            let _ = write!(connect_data, "(SID={sid})");
+        }
+        Some(ConnectData::ServiceName(service)) => {
+            let _ = write!(connect_data, "(SERVICE_NAME={service})");
+        }
+        None => {}
+    }
+    if let Some(server) = dsn.params.get("server") {
+        let _ = write!(connect_data, "(SERVER={})", server.to_ascii_uppercase());
+    }
+    if let Some(instance) = dsn.params.get("instance_name") {
+        let _ = write!(connect_data, "(INSTANCE_NAME={instance})");
+    }
+    if !connect_data.is_empty() {
+        let _ = write!(out, "(CONNECT_DATA={connect_data})");
+    }
+
+    out.push(')');
+    out
+}
+
+fn new_dsn() -> DSN {
+    DSN {
+        driver: "oracle".to_string(),
+        protocol: "tcp".to_string(),
+        ..DSN::default()
+    }
+}
+
+/// Split `user/password@rest` at the last `@`
+fn split_credentials(input: &str) -> (Option<&str>, &str) {
+    // a descriptor never contains '@', but a password might
+    input
+        .rfind('@')
+        .filter(|&at| !input[..at].contains('('))
+        .map_or((None, input), |at| (Some(&input[..at]), &input[at + 1..]))
+}
+
+fn set_credentials(dsn: &mut DSN, credentials: Option<&str>) {
+    let Some(credentials) = credentials else {
+        return;
+    };
+    let (user, pass) = credentials
+        .split_once('/')
+        .map_or((credentials, None), |(u, p)| (u, Some(p)));
+    if !user.is_empty() {
+        dsn.username = Some(user.to_string());
+    }
+    if let Some(pass) = pass.filter(|p| !p.is_empty()) {
+        // passwords with special characters are double-quoted
+        let pass = pass
+            .strip_prefix('"')
+            .and_then(|p| p.strip_suffix('"'))
+            .unwrap_or(pass);
+        dsn.password = Some(pass.into());
+    }
+}
+
+fn set_address(dsn: &mut DSN, host: &str, port: u16) {
+    dsn.address = crate::format_host_port(host, Some(port));
+    dsn.host = Some(host.to_string());
+    dsn.port = Some(port);
+}
+
+fn protocol(name: &str) -> Result<String, ParseError> {
+    match name.to_ascii_lowercase().as_str() {
+        "tcp" => Ok("tcp".to_string()),
+        "tcps" => Ok("tcps".to_string()),
+        _ => Err(ParseError::InvalidProtocol),
+    }
+}
+
+/// A `(KEY=value)` pair of a TNS descriptor
+#[derive(Debug)]
+struct Node<'a> {
+    key: &'a str,
+    value: Value<'a>,
+}
+
+#[derive(Debug)]
+enum Value<'a> {
+    Text(&'a str),
+    List(Vec<Node<'a>>),
+}
+
+fn first_description<'a, 'b>(nodes: &'b [Node<'a>]) -> Option<&'b [Node<'a>]> {
+    nodes.iter().find_map(|node| match (&node.value, node.key) {
+        (Value::List(children), key) if key.eq_ignore_ascii_case("DESCRIPTION") => {
+            Some(children.as_slice())
+        }
+        (Value::List(children), key) if key.eq_ignore_ascii_case("DESCRIPTION_LIST") => {
+            first_description(children)
+        }
+        _ => None,
+    })
+}
+
+fn find<'a, 'b>(nodes: &'b [Node<'a>], key: &str) -> Option<&'b [Node<'a>]> {
+    nodes.iter().find_map(|node| match &node.value {
+        Value::List(children) if node.key.eq_ignore_ascii_case(key) => Some(children.as_slice()),
+        _ => None,
+    })
+}
+
+fn text<'a>(nodes: &[Node<'a>], key: &str) -> Option<&'a str> {
+    nodes.iter().find_map(|node| match node.value {
+        Value::Text(value) if node.key.eq_ignore_ascii_case(key) => Some(value),
+        _ => None,
+    })
+}
+
+/// Recursive descent parser for `(KEY=VALUE)` / `(KEY=(...)(...))` descriptors
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    const fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), ParseError> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(ParseError::InvalidDescriptor)
+        }
+    }
+
+    /// Read up to (not including) the first of `stop`
+    fn token(&mut self, stop: &[u8]) -> &'a str {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let end = rest
+            .bytes()
+            .position(|b| stop.contains(&b))
+            .unwrap_or(rest.len());
+        self.pos += end;
+        rest[..end].trim()
+    }
+
+    /// `(node)(node)...` until `)` or end of input
+    fn parse_list(&mut self) -> Result<Vec<Node<'a>>, ParseError> {
+        let mut nodes = Vec::new();
+        while self.peek() == Some(b'(') {
+            nodes.push(self.parse_node()?);
+        }
+        if nodes.is_empty() {
+            return Err(ParseError::InvalidDescriptor);
+        }
+        Ok(nodes)
+    }
+
+    fn parse_node(&mut self) -> Result<Node<'a>, ParseError> {
+        self.expect(b'(')?;
+        let key = self.token(b"=()");
+        if key.is_empty() {
+            return Err(ParseError::InvalidDescriptor);
+        }
+        self.expect(b'=')?;
+
+        let value = if self.peek() == Some(b'(') {
+            Value::List(self.parse_list()?)
+        } else {
+            Value::Text(self.token(b"()"))
+        };
+        self.expect(b')')?;
+
+        Ok(Node { key, value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConnectData, connect_data, parse, to_descriptor, to_ezconnect};
+    use crate::secret::SecretString;
+    use crate::{DSNBuilder, ParseError};
+
+    #[test]
+    fn test_ezconnect_service_name() {
+        let dsn = parse("app/s3cr@t@db.example.com:1522/ORCLPDB1").unwrap();
+        assert_eq!(dsn.driver, "oracle");
+        assert_eq!(dsn.username.as_deref(), Some("app"));
+        assert_eq!(
+            dsn.password.as_ref().map(SecretString::expose_secret),
+            Some("s3cr@t")
+        );
+        assert_eq!(dsn.protocol, "tcp");
+        assert_eq!(dsn.address, "db.example.com:1522");
+        assert_eq!(dsn.port, Some(1522));
+        assert_eq!(
+            connect_data(&dsn),
+            Some(ConnectData::ServiceName("ORCLPDB1".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_ezconnect_defaults() {
+        let dsn = parse("//db").unwrap();
+        assert_eq!(dsn.host.as_deref(), Some("db"));
+        assert_eq!(dsn.port, Some(1521));
+        assert_eq!(connect_data(&dsn), None);
+    }
+
+    #[test]
+    fn test_ezconnect_instance() {
+        let dsn = parse("db:1521/sales:DEDICATED/inst1").unwrap();
+        assert_eq!(dsn.database.as_deref(), Some("sales"));
+        assert_eq!(dsn.params.get("server").unwrap(), "dedicated");
+        assert_eq!(dsn.params.get("instance_name").unwrap(), "inst1");
+        assert_eq!(to_ezconnect(&dsn), "db:1521/sales:dedicated/inst1");
+    }
+
+    #[test]
+    fn test_ezconnect_errors() {
+        assert!(matches!(parse(":1521/s"), Err(ParseError::MissingHost)));
+        assert!(matches!(parse("db:port/s"), Err(ParseError::InvalidPort)));
+        assert!(matches!(
+            parse("ipc://db/s"),
+            Err(ParseError::InvalidProtocol)
+        ));
+        assert!(matches!(parse("[::1/s"), Err(ParseError::MissingHost)));
+        assert!(matches!(parse("[::1]1521/s"), Err(ParseError::InvalidPort)));
+    }
+
+    #[test]
+    fn test_ezconnect_ipv6() {
+        let dsn = parse("app@[::1]:1522/svc").unwrap();
+        assert_eq!(dsn.host.as_deref(), Some("::1"));
+        assert_eq!(dsn.port, Some(1522));
+        assert_eq!(dsn.address, "[::1]:1522");
+        assert_eq!(dsn.database.as_deref(), Some("svc"));
+        assert_eq!(to_ezconnect(&dsn), "[::1]:1522/svc");
+        assert_eq!(crate::parse(&dsn.to_string()).unwrap().host, dsn.host);
+
+        let dsn = parse("[fe80::1]:1521:ORCL").unwrap();
+        assert_eq!(dsn.host.as_deref(), Some("fe80::1"));
+        assert_eq!(dsn.params.get("sid").unwrap(), "ORCL");
+
+        let dsn = parse("[::1]/svc").unwrap();
+        assert_eq!(dsn.port, Some(1521));
+    }
+
+    #[test]
+    fn test_descriptor_service_name() {
+        let dsn = parse(
+            "scott/tiger@(DESCRIPTION =
+                (ADDRESS_LIST =
+                    (ADDRESS = (PROTOCOL = TCPS)(HOST = db1)(PORT = 2484))
+                    (ADDRESS = (PROTOCOL = TCPS)(HOST = db2)(PORT = 2484)))
+                (CONNECT_DATA = (SERVER = DEDICATED)(SERVICE_NAME = sales.example.com)))",
+        )
+        .unwrap();
+        assert_eq!(dsn.username.as_deref(), Some("scott"));
+        assert_eq!(
+            dsn.password.as_ref().map(SecretString::expose_secret),
+            Some("tiger")
+        );
+        assert_eq!(dsn.protocol, "tcps");
+        assert_eq!(dsn.address, "db1:2484");
+        assert_eq!(dsn.database.as_deref(), Some("sales.example.com"));
+        assert_eq!(dsn.params.get("server").unwrap(), "dedicated");
+    }
+
+    #[test]
+    fn test_descriptor_sid() {
+        let dsn = parse(
+            "(description_list=(description=(address=(protocol=tcp)(host=h)(port=1521))(connect_data=(sid=ORCL))))",
+        )
+        .unwrap();
+        assert_eq!(dsn.database, None);
+        assert_eq!(
+            connect_data(&dsn),
+            Some(ConnectData::Sid("ORCL".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_descriptor_errors() {
+        assert!(matches!(
+            parse("(DESCRIPTION=(ADDRESS=(HOST=h)(PORT=1521))"),
+            Err(ParseError::InvalidDescriptor)
+        ));
+        assert!(matches!(
+            parse("(DESCRIPTION=(CONNECT_DATA=(SID=ORCL)))"),
+            Err(ParseError::MissingAddress)
+        ));
+        assert!(matches!(
+            parse("(DESCRIPTION=(ADDRESS=(PROTOCOL=TCP)(PORT=1521)))"),
+            Err(ParseError::MissingHost)
+        ));
+        assert!(matches!(
+            parse("(ADDRESS=(PROTOCOL=TCP)(HOST=h))"),
+            Err(ParseError::InvalidDescriptor)
+        ));
+        assert!(matches!(
+            parse("(DESCRIPTION=(ADDRESS=(HOST=h)(PORT=1521))) garbage"),
+            Err(ParseError::InvalidDescriptor)
+        ));
+        assert!(matches!(
+            parse("(DESCRIPTION=(ADDRESS=(HOST=h)(PORT=1521)))) "),
+            Err(ParseError::InvalidDescriptor)
+        ));
+        assert!(parse("(DESCRIPTION=(ADDRESS=(HOST=h)(PORT=1521)))\n").is_ok());
+    }
+
+    #[test]
+    fn test_descriptor_roundtrip() {
+        let dsn = DSNBuilder::oracle()
+            .host("db")
+            .port(1522)
+            .service_name("sales")
+            .param("server", "pooled")
+            .build();
+        let descriptor = to_descriptor(&dsn);
+        assert_eq!(
+            descriptor,
+            "(DESCRIPTION=(ADDRESS=(PROTOCOL=TCP)(HOST=db)(PORT=1522))(CONNECT_DATA=(SERVICE_NAME=sales)(SERVER=POOLED)))"
+        );
+
+        let parsed = parse(&descriptor).unwrap();
+        assert_eq!(parsed.host, dsn.host);
+        assert_eq!(parsed.port, dsn.port);
+        assert_eq!(parsed.database, dsn.database);
+        assert_eq!(parsed.params, dsn.params);
+    }
+
+    #[test]
+    fn test_descriptor_ipv6() {
+        let dsn = parse(
+            "(DESCRIPTION=(ADDRESS=(PROTOCOL=TCP)(HOST=::1)(PORT=1521))(CONNECT_DATA=(SERVICE_NAME=s)))",
+        )
+        .unwrap();
+        assert_eq!(dsn.host.as_deref(), Some("::1"));
+        assert_eq!(dsn.address, "[::1]:1521");
+        assert_eq!(
+            to_descriptor(&dsn),
+            "(DESCRIPTION=(ADDRESS=(PROTOCOL=TCP)(HOST=::1)(PORT=1521))(CONNECT_DATA=(SERVICE_NAME=s)))"
+        );
+
+        let reparsed = crate::parse(&dsn.to_string()).unwrap();
+        assert_eq!(reparsed.host, dsn.host);
+        assert_eq!(reparsed.port, dsn.port);
+    }
+
+    #[test]
+    fn test_builder_sid_replaces_service_name() {
+        let dsn = DSNBuilder::oracle()
+            .host("db")
+            .service_name("sales")
+            .sid("ORCL")
+            .build();
+        assert_eq!(dsn.database, None);
+        assert_eq!(to_ezconnect(&dsn), "db:1521:ORCL");
+        assert_eq!(dsn.to_string(), "oracle://tcp(db:1521)?sid=ORCL");
+        assert_eq!(crate::parse(&dsn.to_string()).unwrap().params, dsn.params);
+    }
+}
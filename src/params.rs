@@ -0,0 +1,389 @@
+//! Driver-specific query parameter validation
+//!
+//! `parse` accepts any `key=value` pair, [`DSN::validate_params`] checks the
+//! values of the parameters a driver is known to understand.
+//!
+//! # Examples
+//!
+//! ```
+//! use dsn::{DSNBuilder, params::ParamError};
+//!
+//! let dsn = DSNBuilder::clickhouse()
+//!     .host("localhost")
+//!     .param("secure", "yes")
+//!     .build();
+//!
+//! assert!(matches!(
+//!     dsn.validate_params(),
+//!     Err(ParamError::InvalidValue { .. })
+//! ));
+//! ```
+
+use crate::DSN;
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::{error::Error, fmt, time::Duration};
+
+/// Errors reported by [`DSN::validate_params`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParamError {
+    /// Parameter is not understood by the driver
+    Unknown(String),
+    /// Parameter value is not valid for the driver
+    InvalidValue {
+        /// Parameter name
+        name: String,
+        /// Rejected value
+        value: String,
+    },
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Unknown(name) => write!(f, "unknown parameter: {name}"),
+            Self::InvalidValue { name, value } => {
+                write!(f, "invalid value for parameter {name}: {value}")
+            }
+        }
+    }
+}
+
+impl Error for ParamError {}
+
+impl ParamError {
+    pub(crate) fn invalid(name: &str, value: &str) -> Self {
+        Self::InvalidValue {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+impl DSN {
+    /// Validate the query parameters against the rules of the driver
+    ///
+    /// Only parameters known for the driver are checked, drivers without
+    /// rules always validate. `PostgreSQL` additionally rejects parameters
+    /// libpq does not know, see [`Postgres::validate`](crate::postgres::Postgres::validate).
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParamError::InvalidValue` for the first parameter whose value
+    /// the driver would reject, or `ParamError::Unknown` for an unknown libpq
+    /// parameter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dsn::parse;
+    ///
+    /// let dsn = parse("cassandra://@tcp(node1:9042)/app?consistency=local_quorum").unwrap();
+    /// assert!(dsn.validate_params().is_ok());
+    ///
+    /// let dsn = parse("cassandra://@tcp(node1:9042)/app?consistency=most").unwrap();
+    /// assert!(dsn.validate_params().is_err());
+    /// ```
+    pub fn validate_params(&self) -> Result<(), ParamError> {
+        let check: fn(&str, &str) -> Result<(), ParamError> = match self.driver.as_str() {
+            driver if is_postgres(driver) => return self.postgres().validate(),
+            driver if is_mysql(driver) => return self.mysql().validate(),
+            driver if is_clickhouse(driver) => clickhouse,
+            driver if is_cassandra(driver) => cassandra,
+            driver if is_elasticsearch(driver) => elasticsearch,
+            "kafka" => kafka,
+            _ => return Ok(()),
+        };
+
+        self.params
+            .iter()
+            .try_for_each(|(name, value)| check(name, value))
+    }
+}
+
+/// Whether `driver` names `PostgreSQL`: `postgres`, `postgresql` or `pgsql`
+#[must_use]
+pub fn is_postgres(driver: &str) -> bool {
+    matches!(driver, "postgres" | "postgresql" | "pgsql")
+}
+
+pub(crate) fn is_mysql(driver: &str) -> bool {
+    matches!(driver, "mysql" | "mariadb")
+}
+
+pub(crate) fn is_clickhouse(driver: &str) -> bool {
+    driver == "clickhouse"
+}
+
+pub(crate) fn is_cassandra(driver: &str) -> bool {
+    matches!(driver, "cassandra" | "scylla" | "scylladb")
+}
+
+pub(crate) fn is_elasticsearch(driver: &str) -> bool {
+    matches!(driver, "elasticsearch" | "opensearch")
+}
+
+fn clickhouse(name: &str, value: &str) -> Result<(), ParamError> {
+    match name {
+        "secure" | "compress" | "skip_verify" => parse_bool(name, value).map(drop),
+        "dial_timeout" | "read_timeout" => parse_duration(name, value).map(drop),
+        "compression" => one_of(
+            name,
+            value,
+            &["none", "lz4", "zstd", "gzip", "br", "deflate"],
+        ),
+        _ => Ok(()),
+    }
+}
+
+fn cassandra(name: &str, value: &str) -> Result<(), ParamError> {
+    match name {
+        "ssl" => parse_bool(name, value).map(drop),
+        "timeout" | "connect_timeout" => parse_duration(name, value).map(drop),
+        "consistency" => one_of(
+            name,
+            &value.to_ascii_lowercase(),
+            &[
+                "any",
+                "one",
+                "two",
+                "three",
+                "quorum",
+                "all",
+                "local_quorum",
+                "each_quorum",
+                "local_one",
+                "serial",
+                "local_serial",
+            ],
+        ),
+        "datacenter" if value.is_empty() => Err(ParamError::invalid(name, value)),
+        _ => Ok(()),
+    }
+}
+
+fn elasticsearch(name: &str, value: &str) -> Result<(), ParamError> {
+    match name {
+        "scheme" => one_of(name, value, &["http", "https"]),
+        "sniff" | "compress" => parse_bool(name, value).map(drop),
+        "timeout" => parse_duration(name, value).map(drop),
+        _ => Ok(()),
+    }
+}
+
+fn kafka(name: &str, value: &str) -> Result<(), ParamError> {
+    match name {
+        "security.protocol" => one_of(
+            name,
+            value,
+            &["PLAINTEXT", "SSL", "SASL_PLAINTEXT", "SASL_SSL"],
+        ),
+        "sasl.mechanism" => one_of(
+            name,
+            value,
+            &[
+                "PLAIN",
+                "SCRAM-SHA-256",
+                "SCRAM-SHA-512",
+                "GSSAPI",
+                "OAUTHBEARER",
+            ],
+        ),
+        _ => Ok(()),
+    }
+}
+
+fn one_of(name: &str, value: &str, allowed: &[&str]) -> Result<(), ParamError> {
+    if allowed.contains(&value) {
+        Ok(())
+    } else {
+        Err(ParamError::invalid(name, value))
+    }
+}
+
+/// Parse `true`/`false`/`1`/`0`
+pub(crate) fn parse_bool(name: &str, value: &str) -> Result<bool, ParamError> {
+    match value {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(ParamError::invalid(name, value)),
+    }
+}
+
+/// Parse a Go style duration (`1m30s`, `500ms`) or a number of seconds
+pub(crate) fn parse_duration(name: &str, value: &str) -> Result<Duration, ParamError> {
+    if let Ok(secs) = value.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    let invalid = || ParamError::invalid(name, value);
+    let mut total = Duration::ZERO;
+    let mut rest = value;
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let amount = rest[..digits].parse::<u64>().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+
+        let unit = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let part = match &rest[..unit] {
+            "ns" => Duration::from_nanos(amount),
+            "us" | "µs" => Duration::from_micros(amount),
+            "ms" => Duration::from_millis(amount),
+            "s" => Duration::from_secs(amount),
+            "m" => Duration::from_secs(amount.checked_mul(60).ok_or_else(invalid)?),
+            "h" => Duration::from_secs(amount.checked_mul(3600).ok_or_else(invalid)?),
+            _ => return Err(invalid()),
+        };
+        total = total.checked_add(part).ok_or_else(invalid)?;
+        rest = &rest[unit..];
+    }
+
+    Ok(total)
+}
+
+/// Format a duration the way Go's `time.Duration` does for whole units
+pub(crate) fn format_duration(duration: Duration) -> String {
+    if duration.subsec_nanos() == 0 {
+        format!("{}s", duration.as_secs())
+    } else if duration.subsec_nanos() % 1_000_000 == 0 {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{}ns", duration.as_nanos())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParamError, format_duration, parse_bool, parse_duration};
+    use crate::DSNBuilder;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("t", "10").unwrap(), Duration::from_secs(10));
+        assert_eq!(parse_duration("t", "30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(
+            parse_duration("t", "500ms").unwrap(),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            parse_duration("t", "1h2m3s").unwrap(),
+            Duration::from_secs(3723)
+        );
+        assert!(parse_duration("t", "").is_err());
+        assert!(parse_duration("t", "s").is_err());
+        assert!(parse_duration("t", "10d").is_err());
+        assert!(parse_duration("t", "-1s").is_err());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(30)), "30s");
+        assert_eq!(format_duration(Duration::from_millis(1500)), "1500ms");
+        assert_eq!(format_duration(Duration::from_nanos(10)), "10ns");
+        assert_eq!(
+            parse_duration("t", &format_duration(Duration::from_micros(1500))).unwrap(),
+            Duration::from_micros(1500)
+        );
+    }
+
+    #[test]
+    fn test_parse_bool() {
+        assert!(parse_bool("b", "true").unwrap());
+        assert!(!parse_bool("b", "0").unwrap());
+        assert_eq!(
+            parse_bool("b", "yes"),
+            Err(ParamError::InvalidValue {
+                name: "b".to_string(),
+                value: "yes".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_clickhouse() {
+        let dsn = DSNBuilder::clickhouse()
+            .host("localhost")
+            .secure(true)
+            .param("dial_timeout", "10s")
+            .param("compression", "lz4")
+            .param("max_execution_time", "60")
+            .build();
+        assert_eq!(dsn.validate_params(), Ok(()));
+
+        let dsn = DSNBuilder::clickhouse_http()
+            .host("localhost")
+            .param("compression", "snappy")
+            .build();
+        assert!(dsn.validate_params().is_err());
+    }
+
+    #[test]
+    fn test_validate_cassandra() {
+        let dsn = DSNBuilder::cassandra()
+            .host("node1")
+            .keyspace("app")
+            .datacenter("dc1")
+            .param("consistency", "LOCAL_QUORUM")
+            .build();
+        assert_eq!(dsn.validate_params(), Ok(()));
+
+        let dsn = DSNBuilder::scylladb().host("node1").datacenter("").build();
+        assert!(dsn.validate_params().is_err());
+    }
+
+    #[test]
+    fn test_validate_elasticsearch() {
+        let dsn = DSNBuilder::opensearch()
+            .host("search")
+            .secure(true)
+            .param("sniff", "false")
+            .build();
+        assert_eq!(dsn.validate_params(), Ok(()));
+
+        let dsn = DSNBuilder::elasticsearch()
+            .host("search")
+            .param("scheme", "ftp")
+            .build();
+        assert_eq!(
+            dsn.validate_params().unwrap_err().to_string(),
+            "invalid value for parameter scheme: ftp"
+        );
+    }
+
+    #[test]
+    fn test_validate_kafka() {
+        let dsn = DSNBuilder::kafka()
+            .host("b1")
+            .param("security.protocol", "SASL_SSL")
+            .param("sasl.mechanism", "SCRAM-SHA-512")
+            .build();
+        assert_eq!(dsn.validate_params(), Ok(()));
+
+        let dsn = DSNBuilder::kafka()
+            .host("b1")
+            .param("security.protocol", "TLS")
+            .build();
+        assert!(dsn.validate_params().is_err());
+    }
+
+    #[test]
+    fn test_validate_unknown_driver() {
+        let dsn = DSNBuilder::default()
+            .driver("custom")
+            .param("secure", "maybe")
+            .build();
+        assert_eq!(dsn.validate_params(), Ok(()));
+    }
+}
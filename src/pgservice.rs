@@ -0,0 +1,481 @@
+//! libpq connection service file (`pg_service.conf`) resolution
+//!
+//! A service file groups connection parameters under a service name:
+//!
+//! ```text
+//! [reporting]
+//! host=replica.example.com
+//! port=5433
+//! dbname=analytics
+//! sslmode=verify-full
+//! ```
+//!
+//! [`DSN::resolve_service`] expands the `service` parameter (or `PGSERVICE`)
+//! of a `PostgreSQL` DSN. Like libpq, the per-user file (`PGSERVICEFILE` or
+//! `~/.pg_service.conf`) is searched first, then
+//! `$PGSYSCONFDIR/pg_service.conf`; the first file defining the service is
+//! used.
+//!
+//! Values already present in the DSN override the service values, the
+//! protocol and address can be left out of the DSN to take them from the
+//! service.
+//!
+//! # Examples
+//!
+//! ```
+//! use dsn::{parse, pgservice};
+//!
+//! let file = "[reporting]\nhost=replica.example.com\nport=5433\ndbname=analytics\n";
+//! let mut dsn = parse("postgres://analyst@?service=reporting").unwrap();
+//!
+//! let service = pgservice::find(file, "reporting").unwrap().unwrap();
+//! pgservice::apply(&mut dsn, &service).unwrap();
+//!
+//! assert_eq!(dsn.to_string(), "postgres://analyst@tcp(replica.example.com:5433)/analytics");
+//! ```
+
+use crate::{
+    DSN, DSNBuilder,
+    env::{Env, ProcessEnv},
+    format_host_port,
+    ini::{self, Line},
+    params, postgres,
+};
+use std::{
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Errors that can occur while resolving a service
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ServiceError {
+    /// A service file could not be read
+    Io {
+        /// Service file
+        path: PathBuf,
+        /// Underlying error
+        source: io::Error,
+    },
+    /// Malformed line in a service file
+    Syntax {
+        /// Service file
+        path: PathBuf,
+        /// 1-based line number
+        line: usize,
+    },
+    /// No service file defines the service
+    NotFound(String),
+    /// A service definition sets `service` itself
+    Nested(String),
+    /// The `port` of the service is not a valid port number
+    InvalidPort(String),
+    /// The `host` of the service lists a socket directory with other hosts
+    InvalidHost(String),
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "could not read {}: {source}", path.display()),
+            Self::Syntax { path, line } => {
+                write!(
+                    f,
+                    "syntax error in service file {} at line {line}",
+                    path.display()
+                )
+            }
+            Self::NotFound(service) => write!(f, "definition of service \"{service}\" not found"),
+            Self::Nested(service) => {
+                write!(
+                    f,
+                    "nested service specifications not supported in service \"{service}\""
+                )
+            }
+            Self::InvalidPort(value) => write!(f, "invalid port in service file: {value}"),
+            Self::InvalidHost(value) => write!(f, "invalid host in service file: {value}"),
+        }
+    }
+}
+
+impl Error for ServiceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Key/value pairs of a service definition, in file order
+pub type Service = Vec<(String, String)>;
+
+impl DSN {
+    /// Expand the `service` parameter from the connection service files
+    ///
+    /// Only `PostgreSQL` DSNs are resolved. Returns `false` if neither the
+    /// `service` parameter nor `PGSERVICE` is set, the `service` parameter is
+    /// removed once expanded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ServiceError::NotFound` if no service file defines the
+    /// service, `ServiceError::Io`/`ServiceError::Syntax` for unreadable or
+    /// malformed files, and `ServiceError::Nested`, `ServiceError::InvalidPort`
+    /// or `ServiceError::InvalidHost` for invalid service definitions.
+    pub fn resolve_service(&mut self) -> Result<bool, ServiceError> {
+        self.resolve_service_with(&ProcessEnv)
+    }
+
+    /// Expand the `service` parameter from the service files located through
+    /// `env`
+    ///
+    /// # Errors
+    ///
+    /// See [`resolve_service`](Self::resolve_service).
+    pub fn resolve_service_with(&mut self, env: &impl Env) -> Result<bool, ServiceError> {
+        if !params::is_postgres(&self.driver) {
+            return Ok(false);
+        }
+        let Some(name) = self
+            .params
+            .get("service")
+            .cloned()
+            .or_else(|| env.var("PGSERVICE"))
+            .filter(|name| !name.is_empty())
+        else {
+            return Ok(false);
+        };
+
+        for path in paths(env) {
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(source) => return Err(ServiceError::Io { path, source }),
+            };
+            let service = find(&contents, &name).map_err(|line| ServiceError::Syntax {
+                path: path.clone(),
+                line,
+            })?;
+            if let Some(service) = service {
+                apply(self, &service)?;
+                return Ok(true);
+            }
+        }
+
+        Err(ServiceError::NotFound(name))
+    }
+}
+
+/// Service files in search order: the per-user file, then the system file
+///
+/// The per-user file is `PGSERVICEFILE`, or `~/.pg_service.conf`
+/// (`%APPDATA%\postgresql\.pg_service.conf` on Windows). The system file is
+/// only searched when `PGSYSCONFDIR` is set.
+pub fn paths(env: &impl Env) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    if let Some(file) = env.var("PGSERVICEFILE").filter(|file| !file.is_empty()) {
+        paths.push(PathBuf::from(file));
+    } else if cfg!(windows) {
+        if let Some(dir) = env.var("APPDATA") {
+            paths.push(Path::new(&dir).join("postgresql").join(".pg_service.conf"));
+        }
+    } else if let Some(dir) = env.var("HOME") {
+        paths.push(Path::new(&dir).join(".pg_service.conf"));
+    }
+
+    if let Some(dir) = env.var("PGSYSCONFDIR").filter(|dir| !dir.is_empty()) {
+        paths.push(Path::new(&dir).join("pg_service.conf"));
+    }
+
+    paths
+}
+
+/// Find the definition of `service` in the contents of a service file
+///
+/// # Errors
+///
+/// Returns the 1-based number of the first malformed line.
+pub fn find(contents: &str, service: &str) -> Result<Option<Service>, usize> {
+    let mut definition: Option<Service> = None;
+
+    for (number, line) in contents.lines().enumerate() {
+        // libpq has no includes and requires `key=value`
+        let line = match ini::parse(line).map_err(|_| number + 1)?.pop() {
+            Some(Line::Directive { .. } | Line::Entry { value: None, .. }) => {
+                return Err(number + 1);
+            }
+            Some(line) => line,
+            None => continue,
+        };
+
+        match line {
+            Line::Section(_) if definition.is_some() => break,
+            Line::Section(name) if name == service => definition = Some(Vec::new()),
+            Line::Entry {
+                key,
+                value: Some(value),
+            } => {
+                if let Some(definition) = &mut definition {
+                    definition.push((key.to_string(), value.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(definition)
+}
+
+/// Apply a service definition to `dsn`, keeping the values `dsn` already has
+///
+/// The `service` parameter of `dsn` is removed.
+/// `host`, `port`, `user`, `password` and `dbname` map to the DSN fields,
+/// other keys become parameters.
+/// The address is only taken from the service when the DSN has no protocol;
+/// like libpq, a single port applies to every host and several ports pair
+/// with the hosts in order. A single port also applies when the DSN has a
+/// single host without port.
+///
+/// # Errors
+///
+/// Returns `ServiceError::Nested` if the service sets `service`, or
+/// `ServiceError::InvalidPort` if a port is not a number or the number of
+/// ports does not match the hosts, or `ServiceError::InvalidHost` if a
+/// socket directory is listed with other hosts.
+pub fn apply(dsn: &mut DSN, service: &Service) -> Result<(), ServiceError> {
+    dsn.params.remove("service");
+    let mut hosts = None;
+    let mut port = None;
+    let mut ports = Vec::new();
+
+    for (key, value) in service {
+        match key.as_str() {
+            "service" => return Err(ServiceError::Nested(value.clone())),
+            "host" => hosts = Some(value.as_str()),
+            "port" => {
+                ports = postgres::libpq_ports(value)
+                    .ok_or_else(|| ServiceError::InvalidPort(value.clone()))?;
+                port = Some(value);
+            }
+            "user" => fill(&mut dsn.username, value),
+            "password" => fill(&mut dsn.password, value),
+            "dbname" => fill(&mut dsn.database, value),
+            _ => {
+                dsn.params
+                    .entry(key.clone())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+    }
+
+    if dsn.protocol.is_empty() {
+        if let Some(hosts) = hosts {
+            // one port applies to every host, otherwise there is one per host
+            let located = DSNBuilder::postgres()
+                .libpq_hosts(hosts, &ports)
+                .map_err(|key| match key {
+                    "host" => ServiceError::InvalidHost(hosts.to_string()),
+                    _ => ServiceError::InvalidPort(port.cloned().unwrap_or_default()),
+                })?
+                .build();
+            dsn.protocol = located.protocol;
+            dsn.address = located.address;
+            dsn.host = located.host;
+            dsn.port = located.port;
+            dsn.socket = located.socket;
+        }
+    } else if let (Some(host), None, &[port]) = (&dsn.host, dsn.port, ports.as_slice()) {
+        if dsn.protocol == "tcp" && !dsn.address.contains(',') {
+            dsn.address = format_host_port(host, Some(port));
+            dsn.port = Some(port);
+        }
+    }
+
+    Ok(())
+}
+
+fn fill<T: for<'a> From<&'a str>>(field: &mut Option<T>, value: &str) {
+    if field.is_none() {
+        *field = Some(value.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ServiceError, apply, find};
+    use crate::parse;
+    use std::{fs, path::PathBuf};
+
+    const FILE: &str = "\
+# analytics replicas
+[reporting]
+host=replica1,replica2
+port=5433
+dbname=analytics
+user=reader
+sslmode=verify-full
+
+[local]
+host=/var/run/postgresql
+dbname=app
+";
+
+    fn file(name: &str, contents: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("dsn-pgservice-{}-{name}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_find() {
+        let service = find(FILE, "local").unwrap().unwrap();
+        assert_eq!(
+            service,
+            [
+                ("host".to_string(), "/var/run/postgresql".to_string()),
+                ("dbname".to_string(), "app".to_string()),
+            ]
+        );
+        assert_eq!(find(FILE, "missing"), Ok(None));
+        assert_eq!(find("[a]\nhost\n", "a"), Err(2));
+        assert_eq!(find("[a]\n!include other\n", "a"), Err(2));
+    }
+
+    #[test]
+    fn test_apply() {
+        let service = find(FILE, "reporting").unwrap().unwrap();
+
+        let mut dsn = parse("postgres://?service=reporting").unwrap();
+        apply(&mut dsn, &service).unwrap();
+        assert_eq!(
+            dsn.to_string(),
+            "postgres://reader@tcp(replica1:5433,replica2:5433)/analytics?sslmode=verify-full"
+        );
+
+        // explicit values win
+        let mut dsn = parse("postgres://admin@tcp(primary)/ops?sslmode=disable").unwrap();
+        apply(&mut dsn, &service).unwrap();
+        assert_eq!(
+            dsn.to_string(),
+            "postgres://admin@tcp(primary:5433)/ops?sslmode=disable"
+        );
+
+        let service = find(FILE, "local").unwrap().unwrap();
+        let mut dsn = parse("postgres://").unwrap();
+        apply(&mut dsn, &service).unwrap();
+        assert_eq!(
+            dsn.socket.as_deref(),
+            Some("/var/run/postgresql/.s.PGSQL.5432")
+        );
+
+        let service = find(
+            "[ha]\nhost=db1,db2\nport=5433,5434\noptions=-c search_path=app\n",
+            "ha",
+        )
+        .unwrap()
+        .unwrap();
+        let mut dsn = parse("postgres://?service=ha").unwrap();
+        apply(&mut dsn, &service).unwrap();
+        assert_eq!(dsn.address, "db1:5433,db2:5434");
+        assert_eq!(dsn.params.get("options").unwrap(), "-c search_path=app");
+        assert_eq!(
+            dsn.to_string(),
+            "postgres://tcp(db1:5433,db2:5434)?options=-c%20search_path%3Dapp"
+        );
+        assert_eq!(parse(&dsn.to_string()).unwrap().params, dsn.params);
+    }
+
+    #[cfg(any(feature = "tokio-postgres", feature = "sqlx"))]
+    #[test]
+    fn test_apply_convert() {
+        let service = find(
+            "[app]
+host=db
+options=-c search_path=app
+",
+            "app",
+        )
+        .unwrap()
+        .unwrap();
+        let mut dsn = parse("postgres://app@/app?service=app").unwrap();
+        apply(&mut dsn, &service).unwrap();
+
+        #[cfg(feature = "tokio-postgres")]
+        assert_eq!(
+            ::tokio_postgres::Config::try_from(&dsn)
+                .unwrap()
+                .get_options(),
+            Some("-c search_path=app")
+        );
+        #[cfg(feature = "sqlx")]
+        assert!(::sqlx::postgres::PgConnectOptions::try_from(&dsn).is_ok());
+    }
+
+    #[test]
+    fn test_apply_errors() {
+        let mut dsn = parse("postgres://").unwrap();
+        assert!(matches!(
+            apply(&mut dsn, &find("[a]\nservice=b\n", "a").unwrap().unwrap()),
+            Err(ServiceError::Nested(service)) if service == "b"
+        ));
+        assert!(matches!(
+            apply(&mut dsn, &find("[a]\nport=pg\n", "a").unwrap().unwrap()),
+            Err(ServiceError::InvalidPort(_))
+        ));
+        assert!(matches!(
+            apply(
+                &mut dsn,
+                &find("[a]\nhost=db1,db2,db3\nport=5433,5434\n", "a")
+                    .unwrap()
+                    .unwrap()
+            ),
+            Err(ServiceError::InvalidPort(port)) if port == "5433,5434"
+        ));
+        for hosts in ["/tmp,db", "db,/tmp"] {
+            assert!(matches!(
+                apply(
+                    &mut dsn,
+                    &find(&format!("[a]\nhost={hosts}\n"), "a").unwrap().unwrap()
+                ),
+                Err(ServiceError::InvalidHost(host)) if host == hosts
+            ));
+        }
+    }
+
+    #[test]
+    fn test_resolve_service() {
+        let user = file("user", "[other]\nhost=other\n");
+        let system_dir =
+            std::env::temp_dir().join(format!("dsn-pgservice-{}-sys", std::process::id()));
+        fs::create_dir_all(&system_dir).unwrap();
+        fs::write(system_dir.join("pg_service.conf"), FILE).unwrap();
+
+        let env = |name: &str| match name {
+            "PGSERVICEFILE" => Some(user.display().to_string()),
+            "PGSYSCONFDIR" => Some(system_dir.display().to_string()),
+            _ => None,
+        };
+
+        let mut dsn = parse("postgres://?service=local").unwrap();
+        assert!(dsn.resolve_service_with(&env).unwrap());
+        assert_eq!(dsn.protocol, "unix");
+        assert_eq!(dsn.database.as_deref(), Some("app"));
+        assert!(!dsn.params.contains_key("service"));
+
+        let mut dsn = parse("postgres://?service=unknown").unwrap();
+        assert!(matches!(
+            dsn.resolve_service_with(&env),
+            Err(ServiceError::NotFound(service)) if service == "unknown"
+        ));
+
+        let mut dsn = parse("postgres://app@tcp(db)/app").unwrap();
+        assert!(!dsn.resolve_service_with(&env).unwrap());
+
+        fs::remove_file(user).unwrap();
+        fs::remove_dir_all(system_dir).unwrap();
+    }
+}
@@ -0,0 +1,545 @@
+//! Typed `PostgreSQL` (libpq) options
+//!
+//! [`DSN::postgres`] returns a [`Postgres`] view that reads the libpq
+//! parameters of a parsed DSN as typed values, and the `DSNBuilder` gains the
+//! matching typed setters.
+//!
+//! # Examples
+//!
+//! ```
+//! use dsn::{parse, postgres::SslMode};
+//! use std::time::Duration;
+//!
+//! let dsn = parse("postgres://app@tcp(db:5432)/app?sslmode=verify-full&connect_timeout=10").unwrap();
+//! let pg = dsn.postgres();
+//!
+//! assert_eq!(pg.sslmode().unwrap(), Some(SslMode::VerifyFull));
+//! assert_eq!(pg.connect_timeout().unwrap(), Some(Duration::from_secs(10)));
+//! assert!(pg.validate().is_ok());
+//! ```
+
+use crate::{
+    DSN, DSNBuilder,
+    params::{ParamError, parse_bool},
+};
+use alloc::string::{String, ToString};
+use core::{fmt, str::FromStr, time::Duration};
+
+/// Default `PostgreSQL` port
+pub const DEFAULT_PORT: u16 = 5432;
+
+/// Connection parameters understood by libpq
+pub const KNOWN_PARAMS: &[&str] = &[
+    "application_name",
+    "channel_binding",
+    "client_encoding",
+    "connect_timeout",
+    "dbname",
+    "fallback_application_name",
+    "gssdelegation",
+    "gssencmode",
+    "gsslib",
+    "host",
+    "hostaddr",
+    "keepalives",
+    "keepalives_count",
+    "keepalives_idle",
+    "keepalives_interval",
+    "krbsrvname",
+    "load_balance_hosts",
+    "options",
+    "passfile",
+    "password",
+    "port",
+    "replication",
+    "require_auth",
+    "requirepeer",
+    "requiressl",
+    "service",
+    "ssl_max_protocol_version",
+    "ssl_min_protocol_version",
+    "sslcert",
+    "sslcertmode",
+    "sslcompression",
+    "sslcrl",
+    "sslcrldir",
+    "sslkey",
+    "sslmode",
+    "sslnegotiation",
+    "sslpassword",
+    "sslrootcert",
+    "sslsni",
+    "target_session_attrs",
+    "tcp_user_timeout",
+    "user",
+];
+
+/// libpq `sslmode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SslMode {
+    /// Only try a non-SSL connection
+    Disable,
+    /// Try non-SSL first, then SSL
+    Allow,
+    /// Try SSL first, then non-SSL (libpq default)
+    Prefer,
+    /// Only try SSL, without verifying the certificate
+    Require,
+    /// Only try SSL and verify the server certificate is issued by a trusted CA
+    VerifyCa,
+    /// Like `VerifyCa`, and verify the host name matches the certificate
+    VerifyFull,
+}
+
+impl SslMode {
+    /// The libpq spelling of the mode
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Disable => "disable",
+            Self::Allow => "allow",
+            Self::Prefer => "prefer",
+            Self::Require => "require",
+            Self::VerifyCa => "verify-ca",
+            Self::VerifyFull => "verify-full",
+        }
+    }
+
+    /// Whether the connection is guaranteed to be encrypted
+    #[must_use]
+    pub const fn is_encrypted(self) -> bool {
+        matches!(self, Self::Require | Self::VerifyCa | Self::VerifyFull)
+    }
+}
+
+impl fmt::Display for SslMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SslMode {
+    type Err = ParamError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "disable" => Ok(Self::Disable),
+            "allow" => Ok(Self::Allow),
+            "prefer" => Ok(Self::Prefer),
+            "require" => Ok(Self::Require),
+            "verify-ca" => Ok(Self::VerifyCa),
+            "verify-full" => Ok(Self::VerifyFull),
+            _ => Err(ParamError::invalid("sslmode", s)),
+        }
+    }
+}
+
+/// libpq `target_session_attrs`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TargetSessionAttrs {
+    /// Any successful connection is acceptable (libpq default)
+    Any,
+    /// The session must accept read-write transactions
+    ReadWrite,
+    /// The session must not accept read-write transactions
+    ReadOnly,
+    /// The server must not be in hot standby mode
+    Primary,
+    /// The server must be in hot standby mode
+    Standby,
+    /// Prefer a standby, fall back to any server
+    PreferStandby,
+}
+
+impl TargetSessionAttrs {
+    /// The libpq spelling of the attribute
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Any => "any",
+            Self::ReadWrite => "read-write",
+            Self::ReadOnly => "read-only",
+            Self::Primary => "primary",
+            Self::Standby => "standby",
+            Self::PreferStandby => "prefer-standby",
+        }
+    }
+}
+
+impl fmt::Display for TargetSessionAttrs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TargetSessionAttrs {
+    type Err = ParamError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "any" => Ok(Self::Any),
+            "read-write" => Ok(Self::ReadWrite),
+            "read-only" => Ok(Self::ReadOnly),
+            "primary" => Ok(Self::Primary),
+            "standby" => Ok(Self::Standby),
+            "prefer-standby" => Ok(Self::PreferStandby),
+            _ => Err(ParamError::invalid("target_session_attrs", s)),
+        }
+    }
+}
+
+/// Typed, read-only view over the libpq parameters of a `DSN`
+#[derive(Debug, Clone, Copy)]
+pub struct Postgres<'a> {
+    dsn: &'a DSN,
+}
+
+impl DSN {
+    /// Return a typed view over the libpq parameters
+    #[must_use]
+    pub const fn postgres(&self) -> Postgres<'_> {
+        Postgres { dsn: self }
+    }
+}
+
+impl<'a> Postgres<'a> {
+    /// `sslmode`, `None` when not set (libpq then uses `prefer`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParamError::InvalidValue` if the value is not a libpq mode.
+    pub fn sslmode(&self) -> Result<Option<SslMode>, ParamError> {
+        self.get("sslmode").map(str::parse).transpose()
+    }
+
+    /// `target_session_attrs`, `None` when not set
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParamError::InvalidValue` if the value is not a libpq attribute.
+    pub fn target_session_attrs(&self) -> Result<Option<TargetSessionAttrs>, ParamError> {
+        self.get("target_session_attrs").map(str::parse).transpose()
+    }
+
+    /// `connect_timeout` in seconds, `None` when not set or zero (wait forever)
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParamError::InvalidValue` if the value is not a whole number.
+    pub fn connect_timeout(&self) -> Result<Option<Duration>, ParamError> {
+        self.get("connect_timeout")
+            .map(|value| integer("connect_timeout", value))
+            .transpose()
+            .map(|secs| secs.filter(|&secs| secs > 0).map(Duration::from_secs))
+    }
+
+    /// `application_name`
+    #[must_use]
+    pub fn application_name(&self) -> Option<&'a str> {
+        self.get("application_name")
+    }
+
+    /// Check every parameter is a libpq option with a valid value
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParamError::Unknown` for a parameter libpq does not know, or
+    /// `ParamError::InvalidValue` for a value libpq would reject.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dsn::{parse, params::ParamError};
+    ///
+    /// let dsn = parse("postgres://@tcp(db:5432)/app?sslmode=required").unwrap();
+    /// assert!(matches!(
+    ///     dsn.postgres().validate(),
+    ///     Err(ParamError::InvalidValue { .. })
+    /// ));
+    ///
+    /// let dsn = parse("postgres://@tcp(db:5432)/app?ssl_mode=require").unwrap();
+    /// assert_eq!(
+    ///     dsn.postgres().validate(),
+    ///     Err(ParamError::Unknown("ssl_mode".to_string()))
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), ParamError> {
+        self.dsn
+            .params
+            .iter()
+            .try_for_each(|(name, value)| validate_param(name, value))
+    }
+
+    fn get(self, name: &str) -> Option<&'a str> {
+        self.dsn.params.get(name).map(String::as_str)
+    }
+}
+
+/// Validate a single libpq parameter
+fn validate_param(name: &str, value: &str) -> Result<(), ParamError> {
+    let one_of = |allowed: &[&str]| {
+        if allowed.contains(&value) {
+            Ok(())
+        } else {
+            Err(ParamError::invalid(name, value))
+        }
+    };
+
+    match name {
+        "sslmode" => value.parse::<SslMode>().map(drop),
+        "target_session_attrs" => value.parse::<TargetSessionAttrs>().map(drop),
+        "connect_timeout"
+        | "keepalives_idle"
+        | "keepalives_interval"
+        | "keepalives_count"
+        | "tcp_user_timeout" => integer(name, value).map(drop),
+        "port" => value
+            .parse::<u16>()
+            .map(drop)
+            .map_err(|_| ParamError::invalid(name, value)),
+        "keepalives" | "sslcompression" | "sslsni" | "gssdelegation" | "requiressl" => {
+            parse_bool(name, value).map(drop)
+        }
+        "channel_binding" | "gssencmode" => one_of(&["disable", "prefer", "require"]),
+        "sslcertmode" => one_of(&["disable", "allow", "require"]),
+        "sslnegotiation" => one_of(&["postgres", "direct"]),
+        "load_balance_hosts" => one_of(&["disable", "random"]),
+        "replication" => one_of(&[
+            "true", "on", "yes", "1", "database", "false", "off", "no", "0",
+        ]),
+        "ssl_min_protocol_version" | "ssl_max_protocol_version" => {
+            one_of(&["TLSv1", "TLSv1.1", "TLSv1.2", "TLSv1.3"])
+        }
+        _ if KNOWN_PARAMS.contains(&name) => Ok(()),
+        _ => Err(ParamError::Unknown(name.to_string())),
+    }
+}
+
+fn integer(name: &str, value: &str) -> Result<u64, ParamError> {
+    value
+        .parse::<u64>()
+        .map_err(|_| ParamError::invalid(name, value))
+}
+
+impl DSNBuilder {
+    /// Set the libpq `sslmode`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dsn::{DSNBuilder, postgres::SslMode};
+    ///
+    /// let dsn = DSNBuilder::postgres()
+    ///     .host("db")
+    ///     .sslmode(SslMode::VerifyFull)
+    ///     .build();
+    ///
+    /// assert_eq!(dsn.params.get("sslmode").unwrap(), "verify-full");
+    /// ```
+    #[must_use]
+    pub fn sslmode(self, mode: SslMode) -> Self {
+        self.param("sslmode", mode.as_str())
+    }
+
+    /// Set the libpq `target_session_attrs`
+    #[must_use]
+    pub fn target_session_attrs(self, attrs: TargetSessionAttrs) -> Self {
+        self.param("target_session_attrs", attrs.as_str())
+    }
+
+    /// Set the `application_name` reported to the server
+    #[must_use]
+    pub fn application_name(self, name: impl Into<String>) -> Self {
+        self.param("application_name", name)
+    }
+
+    /// Replace the address with a libpq host list and its ports, e.g.
+    /// `PGHOST` and `PGPORT`
+    ///
+    /// Hosts are comma-separated, a host starting with `/` is the directory
+    /// of the server socket and has to be the only host. Like libpq, a single
+    /// port applies to every host and several ports pair with the hosts in
+    /// order; without ports the builder port is used.
+    ///
+    /// Returns the libpq key at fault, `host` when a socket directory is
+    /// listed with other hosts, or `port` when the number of ports is
+    /// neither one nor that of the hosts.
+    #[cfg(feature = "std")]
+    pub(crate) fn libpq_hosts(mut self, hosts: &str, ports: &[u16]) -> Result<Self, &'static str> {
+        let hosts: Vec<_> = hosts.split(',').collect();
+        let ports = match ports.len() {
+            0 => vec![self.port.unwrap_or(DEFAULT_PORT); hosts.len()],
+            1 => vec![ports[0]; hosts.len()],
+            len if len == hosts.len() => ports.to_vec(),
+            _ => return Err("port"),
+        };
+        let hosts: Vec<_> = hosts
+            .into_iter()
+            .zip(ports)
+            .filter(|(host, _)| !host.is_empty())
+            .collect();
+
+        self.host = None;
+        self.hosts.clear();
+        self.socket = None;
+
+        match hosts.as_slice() {
+            [(dir, port)] if dir.starts_with('/') => Ok(self.socket(socket_path(dir, *port))),
+            hosts if hosts.iter().any(|(host, _)| host.starts_with('/')) => Err("host"),
+            hosts => Ok(hosts
+                .iter()
+                .enumerate()
+                .fold(self, |builder, (i, &(host, port))| {
+                    if i == 0 {
+                        builder.host(host).port(port)
+                    } else {
+                        builder.add_host(host, port)
+                    }
+                })),
+        }
+    }
+}
+
+/// Ports of a libpq port list, e.g. `PGPORT`, an empty entry is the default
+/// port
+#[cfg(feature = "std")]
+pub(crate) fn libpq_ports(ports: &str) -> Option<Vec<u16>> {
+    ports
+        .split(',')
+        .map(|port| match port.trim() {
+            "" => Some(DEFAULT_PORT),
+            port => port.parse().ok(),
+        })
+        .collect()
+}
+
+/// Directory and port of a libpq socket path `<dir>/.s.PGSQL.<port>`, the
+/// whole path without a port when the socket is named otherwise
+///
+/// # Examples
+///
+/// ```
+/// use dsn::postgres::socket_dir;
+///
+/// assert_eq!(socket_dir("/run/postgresql/.s.PGSQL.5433"), ("/run/postgresql", Some(5433)));
+/// assert_eq!(socket_dir("/run/mysqld/mysqld.sock"), ("/run/mysqld/mysqld.sock", None));
+/// ```
+#[must_use]
+pub fn socket_dir(socket: &str) -> (&str, Option<u16>) {
+    socket
+        .rsplit_once("/.s.PGSQL.")
+        .and_then(|(dir, port)| {
+            Some((
+                if dir.is_empty() { "/" } else { dir },
+                Some(port.parse().ok()?),
+            ))
+        })
+        .unwrap_or((socket, None))
+}
+
+/// libpq socket path of the server listening on `port` in `dir`
+///
+/// # Examples
+///
+/// ```
+/// use dsn::postgres::socket_path;
+///
+/// assert_eq!(socket_path("/run/postgresql/", 5432), "/run/postgresql/.s.PGSQL.5432");
+/// ```
+#[must_use]
+pub fn socket_path(dir: &str, port: u16) -> String {
+    alloc::format!("{}/.s.PGSQL.{port}", dir.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SslMode, TargetSessionAttrs};
+    use crate::{DSNBuilder, params::ParamError, parse};
+    use std::time::Duration;
+
+    #[test]
+    fn test_sslmode_roundtrip() {
+        for mode in [
+            SslMode::Disable,
+            SslMode::Allow,
+            SslMode::Prefer,
+            SslMode::Require,
+            SslMode::VerifyCa,
+            SslMode::VerifyFull,
+        ] {
+            assert_eq!(mode.as_str().parse::<SslMode>(), Ok(mode));
+            assert_eq!(mode.to_string(), mode.as_str());
+        }
+        assert!(SslMode::Require.is_encrypted());
+        assert!(!SslMode::Prefer.is_encrypted());
+        assert!("verify_full".parse::<SslMode>().is_err());
+    }
+
+    #[test]
+    fn test_target_session_attrs() {
+        let dsn = parse("postgres://@tcp(db1:5432,db2:5432)/app?target_session_attrs=read-write")
+            .unwrap();
+        assert_eq!(
+            dsn.postgres().target_session_attrs().unwrap(),
+            Some(TargetSessionAttrs::ReadWrite)
+        );
+
+        let dsn = parse("postgres://@tcp(db:5432)/app?target_session_attrs=writer").unwrap();
+        assert!(dsn.postgres().target_session_attrs().is_err());
+    }
+
+    #[test]
+    fn test_view_defaults() {
+        let dsn = parse("postgres://@tcp(db:5432)/app?connect_timeout=0").unwrap();
+        let pg = dsn.postgres();
+        assert_eq!(pg.sslmode(), Ok(None));
+        assert_eq!(pg.target_session_attrs(), Ok(None));
+        assert_eq!(pg.connect_timeout(), Ok(None));
+        assert_eq!(pg.application_name(), None);
+    }
+
+    #[test]
+    fn test_validate() {
+        let dsn = parse(
+            "postgres://@tcp(db:5432)/app?sslmode=require&keepalives=1&gssencmode=disable&sslrootcert=/etc/ca.pem",
+        )
+        .unwrap();
+        assert_eq!(dsn.postgres().validate(), Ok(()));
+
+        let dsn = parse("postgres://@tcp(db:5432)/app?connect_timeout=10s").unwrap();
+        assert_eq!(
+            dsn.postgres().validate(),
+            Err(ParamError::InvalidValue {
+                name: "connect_timeout".to_string(),
+                value: "10s".to_string()
+            })
+        );
+
+        let dsn = parse("postgres://@tcp(db:5432)/app?charset=utf8").unwrap();
+        assert_eq!(
+            dsn.validate_params(),
+            Err(ParamError::Unknown("charset".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_builder_setters() {
+        let dsn = DSNBuilder::postgres()
+            .host("db")
+            .sslmode(SslMode::Require)
+            .target_session_attrs(TargetSessionAttrs::PreferStandby)
+            .application_name("reporting")
+            .connect_timeout(Duration::from_secs(10))
+            .build();
+
+        let pg = dsn.postgres();
+        assert_eq!(pg.sslmode(), Ok(Some(SslMode::Require)));
+        assert_eq!(
+            pg.target_session_attrs(),
+            Ok(Some(TargetSessionAttrs::PreferStandby))
+        );
+        assert_eq!(pg.application_name(), Some("reporting"));
+        assert_eq!(pg.connect_timeout(), Ok(Some(Duration::from_secs(10))));
+        assert_eq!(pg.validate(), Ok(()));
+    }
+}
@@ -0,0 +1,180 @@
+//! `redis::ConnectionInfo` from a `DSN`
+//!
+//! With the `redis` feature, `ConnectionInfo` implements `TryFrom<&DSN>`, so
+//! a [`DSNBuilder::redis`](crate::DSNBuilder::redis) DSN opens a client
+//! directly:
+//!
+//! ```no_run
+//! use redis::{Client, ConnectionInfo};
+//!
+//! let dsn = dsn::parse("redis://:secret@tcp(cache:6379)/2?secure=true").unwrap();
+//! let client = Client::open(ConnectionInfo::try_from(&dsn)?);
+//! # Ok::<(), dsn::ConvertError>(())
+//! ```
+//!
+//! The database is the index selected after connecting, `0` when left out.
+//! TLS is used for the `rediss` driver or `secure=true`, as set by
+//! [`DSNBuilder::secure`](crate::DSNBuilder::secure), and `insecure=true`
+//! skips the certificate host name check. `protocol=resp3` (or `3`) selects
+//! RESP3. Connecting over TLS needs one of the TLS features of `redis`.
+
+use crate::{ConvertError, DSN, params, params::ParamError};
+use ::redis::{
+    ConnectionAddr, ConnectionInfo, IntoConnectionInfo, ProtocolVersion, RedisConnectionInfo,
+};
+use std::path::PathBuf;
+
+/// Connection to the single host or the Unix socket of a `redis` or `rediss` DSN
+impl TryFrom<&DSN> for ConnectionInfo {
+    type Error = ConvertError;
+
+    fn try_from(dsn: &DSN) -> Result<Self, Self::Error> {
+        let flag = |name| {
+            dsn.params
+                .get(name)
+                .map_or(Ok(false), |value| params::parse_bool(name, value))
+        };
+        let tls = match dsn.driver.as_str() {
+            "redis" => flag("secure")?,
+            "rediss" => true,
+            driver => return Err(ConvertError::UnsupportedDriver(driver.to_string())),
+        };
+
+        let addr = match dsn.protocol.as_str() {
+            "tcp" => {
+                let (host, port) = dsn.single_host()?;
+                let host = host.to_string();
+                let port = port.unwrap_or(6379);
+                if tls {
+                    ConnectionAddr::TcpTls {
+                        host,
+                        port,
+                        insecure: flag("insecure")?,
+                        tls_params: None,
+                    }
+                } else {
+                    ConnectionAddr::Tcp(host, port)
+                }
+            }
+            "unix" => {
+                ConnectionAddr::Unix(PathBuf::from(dsn.socket.as_deref().unwrap_or(&dsn.address)))
+            }
+            protocol => return Err(ConvertError::UnsupportedProtocol(protocol.to_string())),
+        };
+
+        let mut settings = RedisConnectionInfo::default();
+        if let Some(database) = &dsn.database {
+            let db = database
+                .parse()
+                .map_err(|_| ParamError::invalid("database", database))?;
+            settings = settings.set_db(db);
+        }
+        if let Some(username) = &dsn.username {
+            settings = settings.set_username(username);
+        }
+        if let Some(password) = &dsn.password {
+            settings = settings.set_password(password.expose_secret());
+        }
+        match dsn.params.get("protocol").map(String::as_str) {
+            None | Some("2" | "resp2") => {}
+            Some("3" | "resp3") => settings = settings.set_protocol(ProtocolVersion::RESP3),
+            Some(value) => return Err(ParamError::invalid("protocol", value).into()),
+        }
+
+        let info = addr
+            .into_connection_info()
+            .expect("a connection address is always valid connection info");
+        Ok(info.set_redis_settings(settings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ConvertError, DSNBuilder, parse};
+    use ::redis::{ConnectionAddr, ConnectionInfo, ProtocolVersion};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_tcp() {
+        let dsn = DSNBuilder::redis()
+            .host("cache")
+            .username("app")
+            .password("secret")
+            .database("2")
+            .build();
+        let info = ConnectionInfo::try_from(&dsn).unwrap();
+        assert_eq!(info.addr(), &ConnectionAddr::Tcp("cache".to_string(), 6379));
+        let settings = info.redis_settings();
+        assert_eq!(settings.db(), 2);
+        assert_eq!(settings.username(), Some("app"));
+        assert_eq!(settings.password(), Some("secret"));
+        assert_eq!(settings.protocol(), ProtocolVersion::RESP2);
+
+        let dsn = DSNBuilder::redis()
+            .host("cache")
+            .secure(true)
+            .param("protocol", "resp3")
+            .build();
+        let info = ConnectionInfo::try_from(&dsn).unwrap();
+        assert_eq!(
+            info.addr(),
+            &ConnectionAddr::TcpTls {
+                host: "cache".to_string(),
+                port: 6379,
+                insecure: false,
+                tls_params: None,
+            }
+        );
+        assert_eq!(info.redis_settings().db(), 0);
+        assert_eq!(info.redis_settings().protocol(), ProtocolVersion::RESP3);
+
+        let dsn = parse("rediss://tcp(cache:6380)?insecure=true").unwrap();
+        let info = ConnectionInfo::try_from(&dsn).unwrap();
+        assert!(matches!(
+            info.addr(),
+            ConnectionAddr::TcpTls {
+                port: 6380,
+                insecure: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_socket() {
+        let dsn = parse("redis://unix(/run/redis/redis.sock)/1").unwrap();
+        let info = ConnectionInfo::try_from(&dsn).unwrap();
+        assert_eq!(
+            info.addr(),
+            &ConnectionAddr::Unix(PathBuf::from("/run/redis/redis.sock"))
+        );
+        assert_eq!(info.redis_settings().db(), 1);
+    }
+
+    #[test]
+    fn test_errors() {
+        for input in [
+            "redis://tcp(cache)/cache",
+            "redis://tcp(cache)/0?secure=maybe",
+            "redis://tcp(cache)/0?protocol=4",
+        ] {
+            let dsn = parse(input).unwrap();
+            assert!(
+                matches!(ConnectionInfo::try_from(&dsn), Err(ConvertError::Param(_))),
+                "{input}"
+            );
+        }
+
+        let dsn = parse("redis://tcp(a,b)/0").unwrap();
+        assert_eq!(
+            ConnectionInfo::try_from(&dsn).unwrap_err(),
+            ConvertError::MultipleHosts
+        );
+
+        let dsn = DSNBuilder::mysql().host("db").build();
+        assert_eq!(
+            ConnectionInfo::try_from(&dsn).unwrap_err(),
+            ConvertError::UnsupportedDriver("mysql".to_string())
+        );
+    }
+}
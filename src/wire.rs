@@ -0,0 +1,245 @@
+//! Just enough of the `PostgreSQL`, `MySQL` and Redis wire protocols to
+//! identify a server, see [`crate::probe`]
+
+use crate::probe::{Server, ServerKind};
+use std::io::{self, Read, Write};
+
+/// Largest message read from a server, anything bigger is not a handshake
+const MAX_MESSAGE: usize = 64 * 1024;
+
+/// `PostgreSQL` `SSLRequest` code
+const SSL_REQUEST: i32 = 80_877_103;
+
+/// `PostgreSQL` protocol 3.0
+const PROTOCOL_3: i32 = 196_608;
+
+/// `MySQL` `CLIENT_SSL` capability
+const CLIENT_SSL: u16 = 0x0800;
+
+/// Stream a handshake is spoken over
+pub trait Stream: Read + Write {}
+
+impl<T: Read + Write> Stream for T {}
+
+/// Ask for TLS, then send a startup message on a plaintext connection
+///
+/// The server version is only sent after authentication, so it is known when
+/// the server trusts `user` without a password.
+pub fn postgres(
+    connect: &dyn Fn() -> io::Result<Box<dyn Stream>>,
+    user: &str,
+    database: Option<&str>,
+) -> io::Result<Server> {
+    let mut stream = connect()?;
+    let mut request = Vec::with_capacity(8);
+    request.extend_from_slice(&8_i32.to_be_bytes());
+    request.extend_from_slice(&SSL_REQUEST.to_be_bytes());
+    stream.write_all(&request)?;
+
+    let mut reply = [0; 1];
+    stream.read_exact(&mut reply)?;
+    let tls = match reply[0] {
+        b'S' => {
+            // the server now expects a TLS handshake, start over without TLS
+            stream = connect()?;
+            Some(true)
+        }
+        b'N' => Some(false),
+        // servers older than 7.0 reject the request with an error
+        b'E' => {
+            stream = connect()?;
+            None
+        }
+        _ => return Err(not("PostgreSQL")),
+    };
+
+    let mut startup = Vec::new();
+    startup.extend_from_slice(&PROTOCOL_3.to_be_bytes());
+    for (key, value) in [("user", Some(user)), ("database", database)] {
+        if let Some(value) = value {
+            push_cstr(&mut startup, key);
+            push_cstr(&mut startup, value);
+        }
+    }
+    push_cstr(&mut startup, "application_name");
+    push_cstr(&mut startup, "dsn-probe");
+    startup.push(0);
+    let len = i32::try_from(startup.len() + 4).map_err(|_| invalid("startup message too long"))?;
+    stream.write_all(&len.to_be_bytes())?;
+    stream.write_all(&startup)?;
+
+    let mut server = Server {
+        kind: ServerKind::Postgres,
+        version: None,
+        tls,
+        message: None,
+    };
+    loop {
+        let (tag, body) = read_postgres_message(&mut stream)?;
+        match tag {
+            // AuthenticationOk, ParameterStatus follow
+            b'R' if body.get(..4) == Some(&[0, 0, 0, 0]) => {}
+            // any other authentication request ends the probe
+            b'R' => return Ok(server),
+            b'S' => {
+                let mut fields = body.split(|&b| b == 0);
+                if fields.next() == Some(b"server_version") {
+                    server.version = fields.next().map(lossy);
+                }
+            }
+            b'K' | b'N' | b'v' => {}
+            b'E' => {
+                server.message = error_field(&body, b'M');
+                return Ok(server);
+            }
+            b'Z' => {
+                // Terminate
+                let _ = stream.write_all(&[b'X', 0, 0, 0, 4]);
+                return Ok(server);
+            }
+            _ => return Err(not("PostgreSQL")),
+        }
+    }
+}
+
+/// Read the greeting the server sends first
+pub fn mysql(stream: &mut dyn Stream) -> io::Result<Server> {
+    let mut header = [0; 4];
+    stream.read_exact(&mut header)?;
+    let len = usize::from(header[0]) | usize::from(header[1]) << 8 | usize::from(header[2]) << 16;
+    if len == 0 || len > MAX_MESSAGE {
+        return Err(not("MySQL"));
+    }
+    let mut payload = vec![0; len];
+    stream.read_exact(&mut payload)?;
+
+    let mut server = Server {
+        kind: ServerKind::MySql,
+        version: None,
+        tls: None,
+        message: None,
+    };
+    match payload[0] {
+        // protocol version 10: version, connection id, auth data, filler, capabilities
+        10 => {
+            let end = payload[1..]
+                .iter()
+                .position(|&b| b == 0)
+                .ok_or_else(|| not("MySQL"))?;
+            server.version = Some(lossy(&payload[1..=end]));
+            let capabilities = end + 1 + 1 + 4 + 8 + 1;
+            server.tls = payload
+                .get(capabilities..capabilities + 2)
+                .map(|flags| u16::from_le_bytes([flags[0], flags[1]]) & CLIENT_SSL != 0);
+        }
+        // ERR packet: code, optional `#` and SQL state, message
+        0xff if len >= 3 => {
+            let message = &payload[3..];
+            let message = match message.first() {
+                Some(b'#') if message.len() >= 6 => &message[6..],
+                _ => message,
+            };
+            server.message = Some(lossy(message));
+        }
+        _ => return Err(not("MySQL")),
+    }
+    Ok(server)
+}
+
+/// `PING`, then `INFO server` for the version
+pub fn redis(stream: &mut dyn Stream) -> io::Result<Server> {
+    let mut server = Server {
+        kind: ServerKind::Redis,
+        version: None,
+        tls: None,
+        message: None,
+    };
+
+    stream.write_all(b"*1\r\n$4\r\nPING\r\n")?;
+    let reply = read_line(stream)?;
+    if let Some(error) = reply.strip_prefix(b"-") {
+        server.message = Some(lossy(error));
+        return Ok(server);
+    }
+    if reply != b"+PONG" {
+        return Err(not("Redis"));
+    }
+
+    stream.write_all(b"*2\r\n$4\r\nINFO\r\n$6\r\nserver\r\n")?;
+    let reply = read_line(stream)?;
+    let Some(len) = reply.strip_prefix(b"$") else {
+        server.message = reply.strip_prefix(b"-").map(lossy);
+        return Ok(server);
+    };
+    let len = std::str::from_utf8(len)
+        .ok()
+        .and_then(|len| len.parse::<usize>().ok())
+        .filter(|&len| len <= MAX_MESSAGE)
+        .ok_or_else(|| not("Redis"))?;
+    let mut info = vec![0; len + 2];
+    stream.read_exact(&mut info)?;
+
+    server.version = info
+        .split(|&b| b == b'\n')
+        .find_map(|line| line.strip_prefix(b"redis_version:"))
+        .map(|version| lossy(version.trim_ascii_end()));
+    Ok(server)
+}
+
+fn read_postgres_message(stream: &mut dyn Stream) -> io::Result<(u8, Vec<u8>)> {
+    let mut header = [0; 5];
+    stream.read_exact(&mut header)?;
+    let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]);
+    let len = usize::try_from(len)
+        .ok()
+        .filter(|len| (4..=MAX_MESSAGE).contains(len))
+        .ok_or_else(|| not("PostgreSQL"))?;
+    let mut body = vec![0; len - 4];
+    stream.read_exact(&mut body)?;
+    Ok((header[0], body))
+}
+
+/// Field `code` of an `ErrorResponse`
+fn error_field(body: &[u8], code: u8) -> Option<String> {
+    let mut rest = body;
+    while let Some((&field, tail)) = rest.split_first() {
+        let end = tail.iter().position(|&b| b == 0)?;
+        if field == code {
+            return Some(lossy(&tail[..end]));
+        }
+        rest = &tail[end + 1..];
+    }
+    None
+}
+
+/// One `\r\n` terminated line, without the terminator
+fn read_line(stream: &mut dyn Stream) -> io::Result<Vec<u8>> {
+    let mut line = Vec::new();
+    let mut byte = [0; 1];
+    while !line.ends_with(b"\r\n") {
+        if line.len() > MAX_MESSAGE {
+            return Err(invalid("line too long"));
+        }
+        stream.read_exact(&mut byte)?;
+        line.push(byte[0]);
+    }
+    line.truncate(line.len() - 2);
+    Ok(line)
+}
+
+fn push_cstr(buf: &mut Vec<u8>, value: &str) {
+    buf.extend_from_slice(value.as_bytes());
+    buf.push(0);
+}
+
+fn lossy(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+fn not(server: &str) -> io::Error {
+    invalid(&format!("not a {server} server"))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}